@@ -0,0 +1,36 @@
+use compiler_rt_build::Properties;
+
+/// Symbols defined by the Rust implementations in `src/fallback`
+const SYMBOLS: &[&str] =
+    &["__adddf3", "__addsf3", "__ashldi3", "__ashlti3", "__ashrdi3", "__ashrti3", "__clzdi2",
+      "__clzsi2", "__clzti2", "__ctzdi2", "__ctzsi2", "__ctzti2", "__divdf3", "__divdi3",
+      "__divmoddi4", "__divmodsi4", "__divsf3", "__divsi3", "__divti3", "__eqdf2", "__eqsf2",
+      "__extendsfdf2", "__fixdfdi", "__fixdfsi", "__fixsfdi", "__fixsfsi", "__fixunsdfdi",
+      "__fixunsdfsi", "__fixunssfdi", "__fixunssfsi", "__floatdidf", "__floatdisf", "__floatsidf",
+      "__floatsisf", "__floatundidf", "__floatundisf", "__floatunsidf", "__floatunsisf", "__gedf2",
+      "__gesf2", "__gtdf2", "__gtsf2", "__ledf2", "__lesf2", "__lshrdi3", "__lshrti3", "__ltdf2",
+      "__ltsf2", "__moddi3", "__modsi3", "__modti3", "__muldf3", "__muldi3", "__mulodi4",
+      "__mulosi4", "__muloti4", "__mulsf3", "__multi3", "__nedf2", "__nesf2", "__popcountdi2",
+      "__popcountsi2", "__popcountti2", "__powidf2", "__powisf2", "__subdf3", "__subsf3",
+      "__truncdfsf2", "__udivdi3", "__udivmoddi4", "__udivmodsi4", "__udivmodti4", "__udivsi3",
+      "__udivti3", "__umoddi3", "__umodsi3", "__umodti3", "__unorddf2", "__unordsf2"];
+
+/// Symbols defined by `src/fallback/aeabi.rs`
+const AEABI_SYMBOLS: &[&str] =
+    &["__aeabi_d2f", "__aeabi_d2iz", "__aeabi_d2lz", "__aeabi_d2uiz", "__aeabi_d2ulz",
+      "__aeabi_dadd", "__aeabi_dcmpeq", "__aeabi_dcmpge", "__aeabi_dcmpgt", "__aeabi_dcmple",
+      "__aeabi_dcmplt", "__aeabi_dcmpun", "__aeabi_ddiv", "__aeabi_dmul", "__aeabi_dsub",
+      "__aeabi_f2d", "__aeabi_f2iz", "__aeabi_f2lz", "__aeabi_f2uiz", "__aeabi_f2ulz",
+      "__aeabi_fadd", "__aeabi_fcmpeq", "__aeabi_fcmpge", "__aeabi_fcmpgt", "__aeabi_fcmple",
+      "__aeabi_fcmplt", "__aeabi_fcmpun", "__aeabi_fdiv", "__aeabi_fmul", "__aeabi_fsub",
+      "__aeabi_i2d", "__aeabi_i2f", "__aeabi_idiv", "__aeabi_idivmod", "__aeabi_l2d",
+      "__aeabi_l2f", "__aeabi_lasr", "__aeabi_ldivmod", "__aeabi_llsl", "__aeabi_llsr",
+      "__aeabi_lmul", "__aeabi_ui2d", "__aeabi_ui2f", "__aeabi_uidiv", "__aeabi_uidivmod",
+      "__aeabi_ul2d", "__aeabi_ul2f", "__aeabi_uldivmod"];
+
+/// Symbols the Rust implementations define for a target with these `properties`
+pub fn symbols(properties: &Properties) -> Vec<String> {
+    let aeabi: &[&str] = if properties.arch == "arm" { AEABI_SYMBOLS } else { &[] };
+
+    SYMBOLS.iter().chain(aeabi).map(|symbol| String::from(*symbol)).collect()
+}
@@ -0,0 +1,208 @@
+extern crate compiler_rt_build;
+
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use compiler_rt_build::{Build, MissingSymbols, Target};
+
+macro_rules! try {
+    ($e:expr) => {
+        $e.unwrap_or_else(|e| panic!("{} with {}", stringify!($e), e))
+    }
+}
+
+mod fallback;
+mod signatures;
+
+fn main() {
+    let target = Target::new(&try!(env::var("TARGET")));
+    let out_dir = PathBuf::from(try!(env::var("OUT_DIR")));
+    println!("cargo:rustc-check-cfg=cfg(fallback)");
+    rerun_if_changed(&target);
+
+    // Without a C toolchain, use the Rust implementations in `src/fallback` instead
+    let forced = env::var_os("CARGO_FEATURE_RUST_FALLBACK").is_some();
+    if forced || !has_compiler(&target) {
+        if !forced {
+            println!("cargo:warning=no C compiler found for {}; using the Rust implementations of \
+                      the intrinsics",
+                     target.name);
+        }
+
+        let properties = target.properties();
+        let defined = fallback::symbols(&properties);
+        declare(&out_dir, &defined, &target);
+        println!("cargo:rustc-cfg=fallback");
+
+        println!("cargo:fallback=true");
+        println!("cargo:cpu={}", target.cpu().unwrap_or(""));
+        println!("cargo:fpu={}",
+                 compiler_rt_build::fpu(&target, &properties).unwrap_or("none"));
+        println!("cargo:float-abi={}", properties.float_abi.name());
+        println!("cargo:symbols={}", defined.join(","));
+        return;
+    }
+
+    let mut build = Build::new(target);
+    build.out_dir(&out_dir)
+        .function_sections(env::var_os("COMPILER_RT_FUNCTION_SECTIONS").is_some())
+        .missing_symbols(missing_symbols());
+    if let Some(symbols) = requested_symbols() {
+        build.symbols(symbols);
+    }
+    if let Some(patches) = env::var_os("COMPILER_RT_PATCHES") {
+        build.patches(patches);
+    }
+
+    let plan = build.plan();
+    declare(&out_dir, &plan.symbols(), build.target());
+
+    // Explain mode: report what would be built and how, but don't build anything
+    if env::var_os("COMPILER_RT_EXPLAIN").is_some() {
+        let explanation = plan.explain();
+        let path = out_dir.join("explain.txt");
+
+        print!("{}", explanation);
+        try!(try!(File::create(&path)).write_all(explanation.as_bytes()));
+        println!("cargo:warning=COMPILER_RT_EXPLAIN is set; nothing was compiled. See {}",
+                 path.display());
+        return;
+    }
+
+    let target = build.target();
+    let properties = target.properties();
+    let fpu = compiler_rt_build::fpu(target, &properties);
+    let output = plan.compile();
+
+    for reason in &output.missing {
+        println!("cargo:warning=missing intrinsic: {}", reason);
+    }
+
+    println!("cargo:rustc-link-lib=static=compiler-rt");
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
+
+    // Metadata for the build scripts of dependent crates, which they get as `DEP_COMPILER_RT_*`
+    // variables
+    println!("cargo:archive={}", output.archive.display());
+    if let Some(ref revision) = output.revision {
+        println!("cargo:revision={}", revision);
+    }
+    println!("cargo:cpu={}", target.cpu().unwrap_or(""));
+    println!("cargo:fpu={}", fpu.unwrap_or("none"));
+    println!("cargo:float-abi={}", properties.float_abi.name());
+    println!("cargo:symbols={}", output.symbols.join(","));
+    println!("cargo:patches={}",
+             output.patches
+                 .iter()
+                 .map(|patch| patch.file_name().unwrap().to_string_lossy().into_owned())
+                 .collect::<Vec<_>>()
+                 .join(","));
+}
+
+/// Writes the Rust declarations of the `defined` intrinsics that can be called on `target`, which
+/// `src/lib.rs` `include!`s
+fn declare(out_dir: &Path, defined: &[String], target: &Target) {
+    let defined = defined.iter()
+        .filter(|symbol| signatures::callable(symbol, target))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    try!(try!(File::create(out_dir.join("intrinsics.rs")))
+        .write_all(signatures::declarations(&defined).as_bytes()));
+
+    // Lets the safe wrappers check if the intrinsic they call is available, e.g.
+    // `#[cfg(intrinsic = "__mulodi4")]`
+    println!("cargo:rustc-check-cfg=cfg(intrinsic, values(any()))");
+    for symbol in defined {
+        println!("cargo:rustc-cfg=intrinsic=\"{}\"", symbol);
+    }
+}
+
+/// Tells Cargo to rerun the build script when one of the variables or files that configure the
+/// build changes
+///
+/// NOTE these directives turn off Cargo's default of rerunning the build script whenever a file of
+/// the package changes, so the target specification file is also listed
+fn rerun_if_changed(target: &Target) {
+    for var in &["COMPILER_RT_EXPLAIN",
+                 "COMPILER_RT_FUNCTION_SECTIONS",
+                 "COMPILER_RT_MISSING_SYMBOLS",
+                 "COMPILER_RT_PATCHES",
+                 "COMPILER_RT_SYMBOLS",
+                 "COMPILER_RT_SYMBOLS_FILE",
+                 "RUST_TARGET_PATH",
+                 "SOURCE_DATE_EPOCH"] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    // NOTE Cargo also reruns the build script when a file is added to or removed from a directory
+    for var in &["COMPILER_RT_SYMBOLS_FILE", "COMPILER_RT_PATCHES"] {
+        if let Some(path) = env::var_os(var) {
+            println!("cargo:rerun-if-changed={}", Path::new(&path).display());
+        }
+    }
+
+    // NOTE like `Target::new`, look for the specification file in the current directory first
+    let spec = format!("{}.json", target.name);
+    let dirs = env::current_dir()
+        .ok()
+        .into_iter()
+        .chain(env::var_os("RUST_TARGET_PATH").map(PathBuf::from));
+    if let Some(spec) = dirs.map(|dir| dir.join(&spec)).find(|spec| spec.exists()) {
+        println!("cargo:rerun-if-changed={}", spec.display());
+    }
+}
+
+/// Whether there's a C compiler for the target
+fn has_compiler(target: &Target) -> bool {
+    let compiler = if target.name != try!(env::var("HOST")) {
+        target.try_tool("CC", "gcc").map(|tool| tool.into_owned())
+    } else {
+        Some(env::var("CC").unwrap_or_else(|_| String::from("cc")))
+    };
+
+    compiler.is_some_and(|cc| Command::new(cc).arg("--version").output().is_ok())
+}
+
+/// What to do about missing intrinsics, according to the `COMPILER_RT_MISSING_SYMBOLS` variable
+fn missing_symbols() -> MissingSymbols {
+    match env::var("COMPILER_RT_MISSING_SYMBOLS").ok().as_deref() {
+        Some("warn") => MissingSymbols::Warn,
+        Some("error") | None => MissingSymbols::Error,
+        Some(other) => {
+            panic!("COMPILER_RT_MISSING_SYMBOLS must be `warn` or `error`, not `{}`",
+                   other)
+        }
+    }
+}
+
+/// Symbols requested via the `COMPILER_RT_SYMBOLS` variable (a comma or whitespace separated list)
+/// and/or via the file the `COMPILER_RT_SYMBOLS_FILE` variable points to (one symbol per line, `#`
+/// starts a comment). `None` means that all the intrinsics should be built.
+fn requested_symbols() -> Option<Vec<String>> {
+    let mut symbols = vec![];
+    let mut requested = false;
+
+    if let Ok(list) = env::var("COMPILER_RT_SYMBOLS") {
+        requested = true;
+        symbols.extend(list.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(String::from));
+    }
+
+    if let Some(path) = env::var_os("COMPILER_RT_SYMBOLS_FILE") {
+        requested = true;
+
+        let contents = &mut String::new();
+        try!(try!(File::open(path)).read_to_string(contents));
+        symbols.extend(contents.lines()
+            .map(|line| line.split('#').next().unwrap_or("").trim())
+            .filter(|s| !s.is_empty())
+            .map(String::from));
+    }
+
+    if requested { Some(symbols) } else { None }
+}
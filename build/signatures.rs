@@ -0,0 +1,320 @@
+use std::fmt::Write;
+
+use compiler_rt_build::Target;
+
+/// Rust signatures of the intrinsics, grouped by the module they are declared in
+// NOTE Not listed, and therefore not declared: intrinsics that operate on types Rust doesn't have
+// (`long double`, `__float128`), routines that don't follow the C calling convention (e.g.
+// `__aeabi_uldivmod` returns its results in r0-r3, `__aeabi_cdcmpeq` in the condition flags) and
+// internal helpers (e.g. `__compilerrt_abort_impl`)
+const SIGNATURES: &[(&str, &[(&str, &str)])] =
+    &[("integer",
+       &[("__absvdi2", "(a: i64) -> i64"),
+         ("__absvsi2", "(a: i32) -> i32"),
+         ("__absvti2", "(a: i128) -> i128"),
+         ("__addvdi3", "(a: i64, b: i64) -> i64"),
+         ("__addvsi3", "(a: i32, b: i32) -> i32"),
+         ("__addvti3", "(a: i128, b: i128) -> i128"),
+         ("__ashldi3", "(a: i64, b: i32) -> i64"),
+         ("__ashlti3", "(a: i128, b: i32) -> i128"),
+         ("__ashrdi3", "(a: i64, b: i32) -> i64"),
+         ("__ashrti3", "(a: i128, b: i32) -> i128"),
+         ("__bswapdi2", "(a: u64) -> u64"),
+         ("__bswapsi2", "(a: u32) -> u32"),
+         ("__clzdi2", "(a: i64) -> i32"),
+         ("__clzsi2", "(a: i32) -> i32"),
+         ("__clzti2", "(a: i128) -> i32"),
+         ("__cmpdi2", "(a: i64, b: i64) -> i32"),
+         ("__cmpti2", "(a: i128, b: i128) -> i32"),
+         ("__ctzdi2", "(a: i64) -> i32"),
+         ("__ctzsi2", "(a: i32) -> i32"),
+         ("__ctzti2", "(a: i128) -> i32"),
+         ("__divdi3", "(a: i64, b: i64) -> i64"),
+         ("__divmoddi4", "(a: i64, b: i64, rem: *mut i64) -> i64"),
+         ("__divmodsi4", "(a: i32, b: i32, rem: *mut i32) -> i32"),
+         ("__divsi3", "(a: i32, b: i32) -> i32"),
+         ("__divti3", "(a: i128, b: i128) -> i128"),
+         ("__ffsdi2", "(a: i64) -> i32"),
+         ("__ffsti2", "(a: i128) -> i32"),
+         ("__lshrdi3", "(a: i64, b: i32) -> i64"),
+         ("__lshrti3", "(a: i128, b: i32) -> i128"),
+         ("__moddi3", "(a: i64, b: i64) -> i64"),
+         ("__modsi3", "(a: i32, b: i32) -> i32"),
+         ("__modti3", "(a: i128, b: i128) -> i128"),
+         ("__muldi3", "(a: i64, b: i64) -> i64"),
+         ("__mulodi4", "(a: i64, b: i64, overflow: *mut i32) -> i64"),
+         ("__mulosi4", "(a: i32, b: i32, overflow: *mut i32) -> i32"),
+         ("__muloti4", "(a: i128, b: i128, overflow: *mut i32) -> i128"),
+         ("__multi3", "(a: i128, b: i128) -> i128"),
+         ("__mulvdi3", "(a: i64, b: i64) -> i64"),
+         ("__mulvsi3", "(a: i32, b: i32) -> i32"),
+         ("__mulvti3", "(a: i128, b: i128) -> i128"),
+         ("__negdi2", "(a: i64) -> i64"),
+         ("__negti2", "(a: i128) -> i128"),
+         ("__negvdi2", "(a: i64) -> i64"),
+         ("__negvsi2", "(a: i32) -> i32"),
+         ("__negvti2", "(a: i128) -> i128"),
+         ("__paritydi2", "(a: i64) -> i32"),
+         ("__paritysi2", "(a: i32) -> i32"),
+         ("__parityti2", "(a: i128) -> i32"),
+         ("__popcountdi2", "(a: i64) -> i32"),
+         ("__popcountsi2", "(a: i32) -> i32"),
+         ("__popcountti2", "(a: i128) -> i32"),
+         ("__subvdi3", "(a: i64, b: i64) -> i64"),
+         ("__subvsi3", "(a: i32, b: i32) -> i32"),
+         ("__subvti3", "(a: i128, b: i128) -> i128"),
+         ("__ucmpdi2", "(a: u64, b: u64) -> i32"),
+         ("__ucmpti2", "(a: u128, b: u128) -> i32"),
+         ("__udivdi3", "(a: u64, b: u64) -> u64"),
+         ("__udivmoddi4", "(a: u64, b: u64, rem: *mut u64) -> u64"),
+         ("__udivmodsi4", "(a: u32, b: u32, rem: *mut u32) -> u32"),
+         ("__udivmodti4", "(a: u128, b: u128, rem: *mut u128) -> u128"),
+         ("__udivsi3", "(a: u32, b: u32) -> u32"),
+         ("__udivti3", "(a: u128, b: u128) -> u128"),
+         ("__umoddi3", "(a: u64, b: u64) -> u64"),
+         ("__umodsi3", "(a: u32, b: u32) -> u32"),
+         ("__umodti3", "(a: u128, b: u128) -> u128")]),
+      ("float",
+       &[("__adddf3", "(a: f64, b: f64) -> f64"),
+         ("__adddf3vfp", "(a: f64, b: f64) -> f64"),
+         ("__addsf3", "(a: f32, b: f32) -> f32"),
+         ("__addsf3vfp", "(a: f32, b: f32) -> f32"),
+         ("__divdf3", "(a: f64, b: f64) -> f64"),
+         ("__divdf3vfp", "(a: f64, b: f64) -> f64"),
+         ("__divsf3", "(a: f32, b: f32) -> f32"),
+         ("__divsf3vfp", "(a: f32, b: f32) -> f32"),
+         ("__eqdf2", "(a: f64, b: f64) -> i32"),
+         ("__eqdf2vfp", "(a: f64, b: f64) -> i32"),
+         ("__eqsf2", "(a: f32, b: f32) -> i32"),
+         ("__eqsf2vfp", "(a: f32, b: f32) -> i32"),
+         ("__gedf2", "(a: f64, b: f64) -> i32"),
+         ("__gedf2vfp", "(a: f64, b: f64) -> i32"),
+         ("__gesf2", "(a: f32, b: f32) -> i32"),
+         ("__gesf2vfp", "(a: f32, b: f32) -> i32"),
+         ("__gtdf2", "(a: f64, b: f64) -> i32"),
+         ("__gtdf2vfp", "(a: f64, b: f64) -> i32"),
+         ("__gtsf2", "(a: f32, b: f32) -> i32"),
+         ("__gtsf2vfp", "(a: f32, b: f32) -> i32"),
+         ("__ledf2", "(a: f64, b: f64) -> i32"),
+         ("__ledf2vfp", "(a: f64, b: f64) -> i32"),
+         ("__lesf2", "(a: f32, b: f32) -> i32"),
+         ("__lesf2vfp", "(a: f32, b: f32) -> i32"),
+         ("__ltdf2", "(a: f64, b: f64) -> i32"),
+         ("__ltdf2vfp", "(a: f64, b: f64) -> i32"),
+         ("__ltsf2", "(a: f32, b: f32) -> i32"),
+         ("__ltsf2vfp", "(a: f32, b: f32) -> i32"),
+         ("__muldf3", "(a: f64, b: f64) -> f64"),
+         ("__muldf3vfp", "(a: f64, b: f64) -> f64"),
+         ("__mulsf3", "(a: f32, b: f32) -> f32"),
+         ("__mulsf3vfp", "(a: f32, b: f32) -> f32"),
+         ("__nedf2", "(a: f64, b: f64) -> i32"),
+         ("__nedf2vfp", "(a: f64, b: f64) -> i32"),
+         ("__negdf2", "(a: f64) -> f64"),
+         ("__negdf2vfp", "(a: f64) -> f64"),
+         ("__negsf2", "(a: f32) -> f32"),
+         ("__negsf2vfp", "(a: f32) -> f32"),
+         ("__nesf2", "(a: f32, b: f32) -> i32"),
+         ("__nesf2vfp", "(a: f32, b: f32) -> i32"),
+         ("__powidf2", "(a: f64, b: i32) -> f64"),
+         ("__powisf2", "(a: f32, b: i32) -> f32"),
+         ("__subdf3", "(a: f64, b: f64) -> f64"),
+         ("__subdf3vfp", "(a: f64, b: f64) -> f64"),
+         ("__subsf3", "(a: f32, b: f32) -> f32"),
+         ("__subsf3vfp", "(a: f32, b: f32) -> f32"),
+         ("__unorddf2", "(a: f64, b: f64) -> i32"),
+         ("__unorddf2vfp", "(a: f64, b: f64) -> i32"),
+         ("__unordsf2", "(a: f32, b: f32) -> i32"),
+         ("__unordsf2vfp", "(a: f32, b: f32) -> i32")]),
+      ("conversion",
+       &[("__extendhfsf2", "(a: u16) -> f32"),
+         ("__extendsfdf2", "(a: f32) -> f64"),
+         ("__extendsfdf2vfp", "(a: f32) -> f64"),
+         ("__fixdfdi", "(a: f64) -> i64"),
+         ("__fixdfsi", "(a: f64) -> i32"),
+         ("__fixdfsivfp", "(a: f64) -> i32"),
+         ("__fixdfti", "(a: f64) -> i128"),
+         ("__fixsfdi", "(a: f32) -> i64"),
+         ("__fixsfsi", "(a: f32) -> i32"),
+         ("__fixsfsivfp", "(a: f32) -> i32"),
+         ("__fixsfti", "(a: f32) -> i128"),
+         ("__fixunsdfdi", "(a: f64) -> u64"),
+         ("__fixunsdfsi", "(a: f64) -> u32"),
+         ("__fixunsdfsivfp", "(a: f64) -> u32"),
+         ("__fixunsdfti", "(a: f64) -> u128"),
+         ("__fixunssfdi", "(a: f32) -> u64"),
+         ("__fixunssfsi", "(a: f32) -> u32"),
+         ("__fixunssfsivfp", "(a: f32) -> u32"),
+         ("__fixunssfti", "(a: f32) -> u128"),
+         ("__floatdidf", "(a: i64) -> f64"),
+         ("__floatdisf", "(a: i64) -> f32"),
+         ("__floatsidf", "(a: i32) -> f64"),
+         ("__floatsidfvfp", "(a: i32) -> f64"),
+         ("__floatsisf", "(a: i32) -> f32"),
+         ("__floatsisfvfp", "(a: i32) -> f32"),
+         ("__floattidf", "(a: i128) -> f64"),
+         ("__floattisf", "(a: i128) -> f32"),
+         ("__floatundidf", "(a: u64) -> f64"),
+         ("__floatundisf", "(a: u64) -> f32"),
+         ("__floatunsidf", "(a: u32) -> f64"),
+         ("__floatunsisf", "(a: u32) -> f32"),
+         ("__floatunssidfvfp", "(a: u32) -> f64"),
+         ("__floatunssisfvfp", "(a: u32) -> f32"),
+         ("__floatuntidf", "(a: u128) -> f64"),
+         ("__floatuntisf", "(a: u128) -> f32"),
+         ("__gnu_f2h_ieee", "(a: f32) -> u16"),
+         ("__gnu_h2f_ieee", "(a: u16) -> f32"),
+         ("__truncdfhf2", "(a: f64) -> u16"),
+         ("__truncdfsf2", "(a: f64) -> f32"),
+         ("__truncdfsf2vfp", "(a: f64) -> f32"),
+         ("__truncsfhf2", "(a: f32) -> u16")]),
+      ("complex",
+       &[("__divdc3", "(a: f64, b: f64, c: f64, d: f64) -> Complex<f64>"),
+         ("__divsc3", "(a: f32, b: f32, c: f32, d: f32) -> Complex<f32>"),
+         ("__muldc3", "(a: f64, b: f64, c: f64, d: f64) -> Complex<f64>"),
+         ("__mulsc3", "(a: f32, b: f32, c: f32, d: f32) -> Complex<f32>")]),
+      ("sync",
+       &[("__sync_fetch_and_add_4", "(ptr: *mut i32, val: i32) -> i32"),
+         ("__sync_fetch_and_add_8", "(ptr: *mut i64, val: i64) -> i64"),
+         ("__sync_fetch_and_and_4", "(ptr: *mut i32, val: i32) -> i32"),
+         ("__sync_fetch_and_and_8", "(ptr: *mut i64, val: i64) -> i64"),
+         ("__sync_fetch_and_max_4", "(ptr: *mut i32, val: i32) -> i32"),
+         ("__sync_fetch_and_max_8", "(ptr: *mut i64, val: i64) -> i64"),
+         ("__sync_fetch_and_min_4", "(ptr: *mut i32, val: i32) -> i32"),
+         ("__sync_fetch_and_min_8", "(ptr: *mut i64, val: i64) -> i64"),
+         ("__sync_fetch_and_nand_4", "(ptr: *mut i32, val: i32) -> i32"),
+         ("__sync_fetch_and_nand_8", "(ptr: *mut i64, val: i64) -> i64"),
+         ("__sync_fetch_and_or_4", "(ptr: *mut i32, val: i32) -> i32"),
+         ("__sync_fetch_and_or_8", "(ptr: *mut i64, val: i64) -> i64"),
+         ("__sync_fetch_and_sub_4", "(ptr: *mut i32, val: i32) -> i32"),
+         ("__sync_fetch_and_sub_8", "(ptr: *mut i64, val: i64) -> i64"),
+         ("__sync_fetch_and_umax_4", "(ptr: *mut u32, val: u32) -> u32"),
+         ("__sync_fetch_and_umax_8", "(ptr: *mut u64, val: u64) -> u64"),
+         ("__sync_fetch_and_umin_4", "(ptr: *mut u32, val: u32) -> u32"),
+         ("__sync_fetch_and_umin_8", "(ptr: *mut u64, val: u64) -> u64"),
+         ("__sync_fetch_and_xor_4", "(ptr: *mut i32, val: i32) -> i32"),
+         ("__sync_fetch_and_xor_8", "(ptr: *mut i64, val: i64) -> i64"),
+         ("__sync_synchronize", "()")]),
+      ("aeabi",
+       &[("__aeabi_d2f", "(a: f64) -> f32"),
+         ("__aeabi_d2h", "(a: f64) -> u16"),
+         ("__aeabi_d2iz", "(a: f64) -> i32"),
+         ("__aeabi_d2lz", "(a: f64) -> i64"),
+         ("__aeabi_d2uiz", "(a: f64) -> u32"),
+         ("__aeabi_d2ulz", "(a: f64) -> u64"),
+         ("__aeabi_dadd", "(a: f64, b: f64) -> f64"),
+         ("__aeabi_dcmpeq", "(a: f64, b: f64) -> i32"),
+         ("__aeabi_dcmpge", "(a: f64, b: f64) -> i32"),
+         ("__aeabi_dcmpgt", "(a: f64, b: f64) -> i32"),
+         ("__aeabi_dcmple", "(a: f64, b: f64) -> i32"),
+         ("__aeabi_dcmplt", "(a: f64, b: f64) -> i32"),
+         ("__aeabi_dcmpun", "(a: f64, b: f64) -> i32"),
+         ("__aeabi_ddiv", "(a: f64, b: f64) -> f64"),
+         ("__aeabi_dmul", "(a: f64, b: f64) -> f64"),
+         ("__aeabi_dneg", "(a: f64) -> f64"),
+         ("__aeabi_drsub", "(a: f64, b: f64) -> f64"),
+         ("__aeabi_dsub", "(a: f64, b: f64) -> f64"),
+         ("__aeabi_f2d", "(a: f32) -> f64"),
+         ("__aeabi_f2h", "(a: f32) -> u16"),
+         ("__aeabi_f2iz", "(a: f32) -> i32"),
+         ("__aeabi_f2lz", "(a: f32) -> i64"),
+         ("__aeabi_f2uiz", "(a: f32) -> u32"),
+         ("__aeabi_f2ulz", "(a: f32) -> u64"),
+         ("__aeabi_fadd", "(a: f32, b: f32) -> f32"),
+         ("__aeabi_fcmpeq", "(a: f32, b: f32) -> i32"),
+         ("__aeabi_fcmpge", "(a: f32, b: f32) -> i32"),
+         ("__aeabi_fcmpgt", "(a: f32, b: f32) -> i32"),
+         ("__aeabi_fcmple", "(a: f32, b: f32) -> i32"),
+         ("__aeabi_fcmplt", "(a: f32, b: f32) -> i32"),
+         ("__aeabi_fcmpun", "(a: f32, b: f32) -> i32"),
+         ("__aeabi_fdiv", "(a: f32, b: f32) -> f32"),
+         ("__aeabi_fmul", "(a: f32, b: f32) -> f32"),
+         ("__aeabi_fneg", "(a: f32) -> f32"),
+         ("__aeabi_frsub", "(a: f32, b: f32) -> f32"),
+         ("__aeabi_fsub", "(a: f32, b: f32) -> f32"),
+         ("__aeabi_h2f", "(a: u16) -> f32"),
+         ("__aeabi_i2d", "(a: i32) -> f64"),
+         ("__aeabi_i2f", "(a: i32) -> f32"),
+         ("__aeabi_idiv", "(a: i32, b: i32) -> i32"),
+         ("__aeabi_l2d", "(a: i64) -> f64"),
+         ("__aeabi_l2f", "(a: i64) -> f32"),
+         ("__aeabi_lasr", "(a: i64, b: i32) -> i64"),
+         ("__aeabi_lcmp", "(a: i64, b: i64) -> i32"),
+         ("__aeabi_llsl", "(a: i64, b: i32) -> i64"),
+         ("__aeabi_llsr", "(a: i64, b: i32) -> i64"),
+         ("__aeabi_lmul", "(a: i64, b: i64) -> i64"),
+         ("__aeabi_memclr", "(dest: *mut u8, n: usize)"),
+         ("__aeabi_memclr4", "(dest: *mut u8, n: usize)"),
+         ("__aeabi_memclr8", "(dest: *mut u8, n: usize)"),
+         ("__aeabi_memcmp", "(a: *const u8, b: *const u8, n: usize) -> i32"),
+         ("__aeabi_memcmp4", "(a: *const u8, b: *const u8, n: usize) -> i32"),
+         ("__aeabi_memcmp8", "(a: *const u8, b: *const u8, n: usize) -> i32"),
+         ("__aeabi_memcpy", "(dest: *mut u8, src: *const u8, n: usize)"),
+         ("__aeabi_memcpy4", "(dest: *mut u8, src: *const u8, n: usize)"),
+         ("__aeabi_memcpy8", "(dest: *mut u8, src: *const u8, n: usize)"),
+         ("__aeabi_memmove", "(dest: *mut u8, src: *const u8, n: usize)"),
+         ("__aeabi_memmove4", "(dest: *mut u8, src: *const u8, n: usize)"),
+         ("__aeabi_memmove8", "(dest: *mut u8, src: *const u8, n: usize)"),
+         ("__aeabi_memset", "(dest: *mut u8, n: usize, c: i32)"),
+         ("__aeabi_memset4", "(dest: *mut u8, n: usize, c: i32)"),
+         ("__aeabi_memset8", "(dest: *mut u8, n: usize, c: i32)"),
+         ("__aeabi_ui2d", "(a: u32) -> f64"),
+         ("__aeabi_ui2f", "(a: u32) -> f32"),
+         ("__aeabi_uidiv", "(a: u32, b: u32) -> u32"),
+         ("__aeabi_ul2d", "(a: u64) -> f64"),
+         ("__aeabi_ul2f", "(a: u64) -> f32"),
+         ("__aeabi_ulcmp", "(a: u64, b: u64) -> i32")])];
+
+/// Intrinsics that return a `_Complex float` or a `_Complex double`, which Rust declares as returning
+/// a `#[repr(C)]` `Complex` struct
+const COMPLEX: &[&str] = &["__divdc3", "__divsc3", "__muldc3", "__mulsc3"];
+
+/// Whether `symbol` can be declared, and called, from Rust on `target`
+///
+/// ARM, AArch64 and x86_64 (System V) return a complex number like a struct of its two parts.
+/// Other ABIs don't, e.g. 32-bit x86 returns the struct through memory but the complex number in
+/// registers, so the intrinsics in `COMPLEX` aren't declared there.
+pub fn callable(symbol: &str, target: &Target) -> bool {
+    if !COMPLEX.contains(&symbol) {
+        return true;
+    }
+
+    match target.arch() {
+        "arm" | "aarch64" => true,
+        "x86_64" => target.os() != "windows",
+        _ => false,
+    }
+}
+
+/// Generates the Rust declarations of the intrinsics in `symbols`
+///
+/// Each group of intrinsics becomes a module (`integer`, `float`, etc.) that contains an `extern`
+/// block; empty modules are omitted.
+pub fn declarations(symbols: &[String]) -> String {
+    let mut s = String::new();
+
+    writeln!(s, "// Generated by build/signatures.rs. Don't edit!").unwrap();
+    for &(module, signatures) in SIGNATURES {
+        let signatures = signatures.iter()
+            .filter(|&&(symbol, _)| symbols.iter().any(|s| s == symbol))
+            .collect::<Vec<_>>();
+
+        if signatures.is_empty() {
+            continue;
+        }
+
+        writeln!(s, "\npub mod {} {{", module).unwrap();
+        if module == "complex" {
+            writeln!(s, "    use Complex;\n").unwrap();
+        }
+        // NOTE the run-time ABI routines always use the base (soft-float) procedure call standard
+        let abi = if module == "aeabi" { "aapcs" } else { "C" };
+        writeln!(s, "    extern \"{}\" {{", abi).unwrap();
+        for &&(symbol, signature) in &signatures {
+            writeln!(s, "        pub fn {}{};", symbol, signature).unwrap();
+        }
+        writeln!(s, "    }}").unwrap();
+        writeln!(s, "}}").unwrap();
+    }
+
+    s
+}
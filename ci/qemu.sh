@@ -0,0 +1,88 @@
+# Runs compiler-rt's unit tests against the archive built for $TARGET under QEMU
+#
+# Each `test/builtins/Unit/foo_test.c` of a source that's in the archive is compiled with the
+# compiler and flags the archive was built with, linked against the archive and newlib's
+# semihosting library (rdimon), and executed with `qemu-arm`.
+#
+# Usage: cargo build --target $TARGET && TARGET=$TARGET bash ci/qemu.sh
+
+set -e
+
+. $(dirname $0)/utils.sh
+
+# QEMU model of the target's core
+qemu_cpu() {
+    case $TARGET in
+        cortex-m0|cortex-m0plus|cortex-m1|thumbv6m-none-eabi)
+            echo cortex-m0
+            ;;
+        cortex-m3|thumbv7m-none-eabi)
+            echo cortex-m3
+            ;;
+        cortex-m4|cortex-m4f|thumbv7em-none-eabi)
+            echo cortex-m4
+            ;;
+        cortex-m7|cortex-m7f|cortex-m7f-sp)
+            echo cortex-m7
+            ;;
+        *)
+            die "no QEMU model for $TARGET"
+            ;;
+    esac
+}
+
+# Symbols of the archive grouped by the stem of the source that defines them: "$stem\t$symbols"
+intrinsics() {
+    jq -r '.[] | "\(.source) \(.name)"' $1 | \
+        awk '{
+            n = split($1, path, "/")
+            stem = path[n]
+            sub(/\.[cS]$/, "", stem)
+            symbols[stem] = symbols[stem] ? symbols[stem] ", " $2 : $2
+        }
+        END { for (stem in symbols) print stem "\t" symbols[stem] }' | \
+        sort
+}
+
+main() {
+    local archive=$(ls -t target/$TARGET/debug/build/compiler-rt-*/out/libcompiler-rt.a | head -n 1)
+    [ -f "$archive" ] || die "no archive for $TARGET; run \`cargo build --target $TARGET\` first"
+
+    local out=$(dirname $archive)
+    local cc=$(sed -n 's/^compiler: //p' $out/../output)
+    local flags=$(cat $out/flags.txt)
+    local cpu=$(qemu_cpu)
+    local td=$(mktemp -d)
+
+    local passed=0 failed=0 untested=
+    while IFS=$'\t' read -r stem symbols; do
+        local test=$out/unit/${stem}_test.c
+        if [ ! -f $test ]; then
+            untested="$untested $stem"
+            continue
+        fi
+
+        if ! $cc $flags --specs=rdimon.specs -I $out/unit/include $test $archive -lm \
+             -o $td/$stem > $td/$stem.log 2>&1; then
+            failed=$((failed + 1))
+            echo "$symbols ... ERROR"
+            cat $td/$stem.log
+        elif ! timeout 60 qemu-arm -cpu $cpu $td/$stem > $td/$stem.log 2>&1 < /dev/null; then
+            failed=$((failed + 1))
+            echo "$symbols ... FAILED"
+            cat $td/$stem.log
+        else
+            passed=$((passed + 1))
+            echo "$symbols ... ok"
+        fi
+    done < <(intrinsics $out/symbols.json)
+
+    rm -rf $td
+
+    echo
+    echo "$passed passed; $failed failed; without a unit test:$untested"
+
+    [ $failed -eq 0 ]
+}
+
+main
@@ -8,23 +8,30 @@ main() {
             export AR_no_linker_field=arm-none-eabi-ar
             export CC_no_linker_field=arm-none-eabi-gcc
             ;;
+        # FIXME the ARMv6-M builds lack `__aeabi_[u]ldivmod` and the `__aeabi_{d,f}cmp*` functions
+        cortex-m0|cortex-m0plus|cortex-m1|thumbv6m-none-eabi)
+            export COMPILER_RT_MISSING_SYMBOLS=warn
+            ;;
     esac
 
     cargo build --target $TARGET
 
     case $TARGET in
         cortex-m*|thumbv*)
-            set +x
             local staticlib=$(find -name libcompiler-rt.a)
-            local symbols=$(arm-none-eabi-nm -Cg --defined-only $staticlib | grep '^[0-9]' | cut -d' ' -f3)
-
-            for symbol in $symbols; do
-                echo $symbol
-            done
-            echo "Total: $(echo $symbols | wc -w) symbols"
+            cat $(dirname $staticlib)/symbols.json
 
-            set -x
             arm-none-eabi-readelf -A $staticlib
+
+            # compiler-rt's own unit tests, under QEMU
+            bash $(dirname $0)/qemu.sh
+        ;;
+        x86_64-unknown-linux-gnu)
+            # compiler-rt's own unit tests
+            cargo test --target $TARGET -- --nocapture
+
+            # golden snapshots of the build of the target specification files
+            cargo test -p compiler-rt-build
         ;;
     esac
 }
@@ -0,0 +1,145 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Size of the header that precedes each archive member
+const HEADER_SIZE: usize = 60;
+/// Global header of `ar` archives
+const MAGIC: &[u8] = b"!<arch>\n";
+
+/// Creates the `archive` from the `objects` in a reproducible way
+///
+/// Members are sorted by name and their timestamps, owner and group are zeroed (`ar`'s `D`
+/// modifier). If `SOURCE_DATE_EPOCH` is set, it's used as the timestamp of all the members instead.
+pub fn create(archiver: &str, archive: &Path, objects: &[PathBuf]) {
+    let epoch = env::var("SOURCE_DATE_EPOCH").ok().map(|epoch| source_date_epoch(&epoch));
+
+    // `ar` appends to existing archives
+    if archive.exists() {
+        try!(fs::remove_file(archive));
+    }
+
+    let mut objects = objects.iter().collect::<Vec<_>>();
+    objects.sort_by_key(|object| object.file_name());
+
+    let mut cmd = Command::new(archiver);
+    cmd.arg("crsD").arg(archive).args(&objects);
+    println!("running: {:?}", cmd);
+    assert!(try!(cmd.status()).success(), "{} failed", archiver);
+
+    if let Some(epoch) = epoch {
+        let bytes = &mut vec![];
+        try!(try!(File::open(archive)).read_to_end(bytes));
+        set_timestamps(bytes, epoch);
+        try!(try!(File::create(archive)).write_all(bytes));
+    }
+}
+
+/// A file stored in an archive
+pub struct Member<'a> {
+    pub name: String,
+    pub data: &'a [u8],
+}
+
+/// Returns the object files stored in the `archive`, skipping the symbol index and the long name
+/// table, or `None` if `bytes` is not an `ar` archive
+pub fn members<'a>(bytes: &'a [u8]) -> Option<Vec<Member<'a>>> {
+    if !bytes.starts_with(MAGIC) {
+        return None;
+    }
+
+    let headers = headers(bytes);
+
+    // GNU `ar` stores names longer than 15 bytes in the `//` member; the header of each such member
+    // then contains `/` + the offset of its name into that table
+    let long_names = headers.iter()
+        .find(|&&(offset, _)| &bytes[offset..offset + 3] == b"// ")
+        .map(|&(offset, size)| &bytes[offset + HEADER_SIZE..offset + HEADER_SIZE + size])
+        .unwrap_or(&[]);
+
+    let members = headers.iter()
+        .filter_map(|&(offset, size)| {
+            let name = String::from_utf8_lossy(&bytes[offset..offset + 16]).trim_end().to_owned();
+            let mut data = &bytes[offset + HEADER_SIZE..offset + HEADER_SIZE + size];
+
+            let name = if name == "/" || name == "//" || name == "/SYM64/" {
+                return None;
+            } else if let Some(len) = name.strip_prefix("#1/") {
+                // BSD `ar` stores names that are long or contain spaces right before the member's
+                // data, whose size includes the name
+                let len = try!(len.parse::<usize>());
+                let name = String::from_utf8_lossy(&data[..len]).trim_end_matches('\0').to_owned();
+                data = &data[len..];
+
+                // BSD symbol index
+                if name.starts_with("__.SYMDEF") {
+                    return None;
+                }
+
+                name
+            } else if name.starts_with("__.SYMDEF") {
+                return None;
+            } else if let Some(offset) = name.strip_prefix('/') {
+                let start = try!(offset.parse::<usize>());
+                let end = start +
+                          long_names[start..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .unwrap_or(long_names.len() - start);
+
+                String::from_utf8_lossy(&long_names[start..end]).trim_end_matches('/').to_owned()
+            } else {
+                name.trim_end_matches('/').to_owned()
+            };
+
+            Some(Member {
+                name,
+                data,
+            })
+        })
+        .collect();
+
+    Some(members)
+}
+
+/// Parses the value of `SOURCE_DATE_EPOCH`, which has to fit in the 12 digit timestamp field of the
+/// member headers
+fn source_date_epoch(epoch: &str) -> u64 {
+    match epoch.parse::<u64>() {
+        Ok(epoch) if epoch <= 999_999_999_999 => epoch,
+        _ => {
+            panic!("SOURCE_DATE_EPOCH must be a number of seconds of at most 12 digits, not `{}`",
+                   epoch)
+        }
+    }
+}
+
+/// Overwrites the modification time of all the members of the archive contained in `bytes`
+fn set_timestamps(bytes: &mut [u8], timestamp: u64) {
+    let timestamp = format!("{:<12}", timestamp);
+    for (offset, _) in headers(bytes) {
+        bytes[offset + 16..offset + 28].copy_from_slice(timestamp.as_bytes());
+    }
+}
+
+/// Returns the offset of the header and the size of each member of the archive in `bytes`
+fn headers(bytes: &[u8]) -> Vec<(usize, usize)> {
+    assert!(bytes.starts_with(MAGIC), "not an `ar` archive");
+
+    let mut headers = vec![];
+    let mut offset = MAGIC.len();
+    while offset + HEADER_SIZE <= bytes.len() {
+        let size = try!(String::from_utf8_lossy(&bytes[offset + 48..offset + 58])
+            .trim()
+            .parse::<usize>());
+
+        headers.push((offset, size));
+
+        // Members are 2-byte aligned
+        offset += HEADER_SIZE + size + size % 2;
+    }
+
+    headers
+}
@@ -0,0 +1,120 @@
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use gcc::Tool;
+
+/// 64-bit FNV-1a
+///
+/// NOTE unlike `DefaultHasher`, its output is stable across Rust releases, so the keys recorded by
+/// one build can be compared against the ones computed by the next
+#[derive(Clone)]
+pub struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Fnv {
+        Fnv(0xcbf29ce484222325)
+    }
+
+    /// Hashes `bytes` followed by a separator, so that consecutive fields can't run into each other
+    fn field(&mut self, bytes: &[u8]) {
+        self.write(bytes);
+        self.write(&(bytes.len() as u64).to_le_bytes());
+    }
+}
+
+impl Hasher for Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Hashes what every object of the `src` checkout depends on: the compiler, its flags and all the
+/// headers of `lib/builtins`
+///
+/// NOTE the headers aren't tracked per source, so changing any of them recompiles everything
+pub fn common(compiler: &Tool, src: &Path) -> Fnv {
+    let mut hasher = Fnv::new();
+
+    // NOTE the checkout is usually a fresh temporary directory whose path appears in the prefix
+    // map flags, but not in the objects
+    let location = src.display().to_string();
+    hasher.field(compiler.path().to_string_lossy().as_bytes());
+    for arg in compiler.args() {
+        hasher.field(arg.to_string_lossy().replace(&location, "/compiler-rt").as_bytes());
+    }
+
+    let builtins = src.join("lib/builtins");
+    let mut headers = vec![];
+    find_headers(&builtins, &mut headers);
+    headers.sort();
+    for header in headers {
+        hasher.field(header.strip_prefix(&builtins).unwrap().to_string_lossy().as_bytes());
+        hasher.field(&read(&header).unwrap_or_default());
+    }
+
+    hasher
+}
+
+/// Key of the object compiled from `input`, the compiler-rt `source` or a rewritten copy of it, or
+/// `None` if `input` can't be read
+pub fn key(common: &Fnv, source: &str, input: &Path) -> Option<String> {
+    let mut hasher = common.clone();
+    hasher.field(source.as_bytes());
+    read(input).map(|contents| {
+        hasher.field(&contents);
+        format!("{:016x}", hasher.finish())
+    })
+}
+
+/// Whether `object` exists and was compiled from sources and flags with the same `key`
+pub fn is_fresh(object: &Path, key: &str) -> bool {
+    object.exists() && read(&stamp(object)).map(|recorded| recorded == key.as_bytes()) == Some(true)
+}
+
+/// Records that `object` was compiled from sources and flags with this `key`
+pub fn record(object: &Path, key: &str) {
+    try!(try!(File::create(stamp(object))).write_all(key.as_bytes()));
+}
+
+/// Forgets the key of `object`, which is about to be recompiled
+pub fn forget(object: &Path) {
+    let stamp = stamp(object);
+    if stamp.exists() {
+        try!(fs::remove_file(stamp));
+    }
+}
+
+/// File where the key of `object` is recorded
+fn stamp(object: &Path) -> PathBuf {
+    object.with_extension("o.key")
+}
+
+fn find_headers(dir: &Path, headers: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries {
+        let path = try!(entry).path();
+        if path.is_dir() {
+            find_headers(&path, headers);
+        } else if path.extension().map(|ext| ext == "h" || ext == "inc") == Some(true) {
+            headers.push(path);
+        }
+    }
+}
+
+fn read(path: &Path) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    File::open(path).and_then(|mut f| f.read_to_end(&mut bytes)).ok().map(|_| bytes)
+}
@@ -0,0 +1,183 @@
+//! Just enough of an ELF parser to list the symbols an object file defines
+
+/// Section header type of the symbol table
+const SHT_SYMTAB: u32 = 2;
+/// Section index of undefined symbols
+const SHN_UNDEF: u16 = 0;
+/// Section index of symbols with an absolute value
+const SHN_ABS: u16 = 0xfff1;
+/// Section index of common symbols
+const SHN_COMMON: u16 = 0xfff2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Binding {
+    Local,
+    Global,
+    Weak,
+}
+
+impl Binding {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Binding::Local => "local",
+            Binding::Global => "global",
+            Binding::Weak => "weak",
+        }
+    }
+}
+
+/// A symbol *defined* by an object file
+pub struct Symbol {
+    pub name: String,
+    pub binding: Binding,
+    pub size: u64,
+    pub section: String,
+}
+
+/// Reads integers with the object's endianness and word size
+struct Reader<'a> {
+    bytes: &'a [u8],
+    is_64: bool,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn uint(&self, offset: usize, size: usize) -> u64 {
+        let bytes = &self.bytes[offset..offset + size];
+
+        if self.big_endian {
+            bytes.iter().fold(0, |n, &b| n << 8 | b as u64)
+        } else {
+            bytes.iter().rev().fold(0, |n, &b| n << 8 | b as u64)
+        }
+    }
+
+    fn u16(&self, offset: usize) -> u16 {
+        self.uint(offset, 2) as u16
+    }
+
+    fn u32(&self, offset: usize) -> u32 {
+        self.uint(offset, 4) as u32
+    }
+
+    /// `Elf32_Addr`/`Elf32_Off`/`Elf32_Word` or their 64-bit counterparts
+    fn word(&self, offset: usize) -> u64 {
+        self.uint(offset, if self.is_64 { 8 } else { 4 })
+    }
+
+    /// NUL terminated string at `offset`
+    fn str(&self, offset: usize) -> String {
+        let bytes = &self.bytes[offset..];
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+
+        String::from_utf8_lossy(&bytes[..end]).into_owned()
+    }
+}
+
+/// A section header
+struct Section {
+    name: u32,
+    type_: u32,
+    offset: usize,
+    size: usize,
+    link: u32,
+    entsize: usize,
+}
+
+/// Returns the non-local symbols that the ELF object in `bytes` defines, or `None` if `bytes` is not
+/// an ELF object (e.g. a Mach-O or COFF one)
+pub fn defined_symbols(bytes: &[u8]) -> Option<Vec<Symbol>> {
+    if !bytes.starts_with(b"\x7fELF") {
+        return None;
+    }
+
+    let r = Reader {
+        bytes,
+        is_64: bytes[4] == 2,
+        big_endian: bytes[5] == 2,
+    };
+
+    let (shoff, shentsize, shnum, shstrndx) = if r.is_64 {
+        (r.word(0x28) as usize, r.u16(0x3a) as usize, r.u16(0x3c) as usize, r.u16(0x3e) as usize)
+    } else {
+        (r.word(0x20) as usize, r.u16(0x2e) as usize, r.u16(0x30) as usize, r.u16(0x32) as usize)
+    };
+
+    let sections = (0..shnum)
+        .map(|i| {
+            let h = shoff + i * shentsize;
+
+            if r.is_64 {
+                Section {
+                    name: r.u32(h),
+                    type_: r.u32(h + 0x04),
+                    offset: r.word(h + 0x18) as usize,
+                    size: r.word(h + 0x20) as usize,
+                    link: r.u32(h + 0x28),
+                    entsize: r.word(h + 0x38) as usize,
+                }
+            } else {
+                Section {
+                    name: r.u32(h),
+                    type_: r.u32(h + 0x04),
+                    offset: r.word(h + 0x10) as usize,
+                    size: r.word(h + 0x14) as usize,
+                    link: r.u32(h + 0x18),
+                    entsize: r.word(h + 0x24) as usize,
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let section_name = |index: u16| -> String {
+        match index {
+            SHN_ABS => String::from("*ABS*"),
+            SHN_COMMON => String::from("*COM*"),
+            _ => {
+                match (sections.get(index as usize), sections.get(shstrndx)) {
+                    (Some(section), Some(shstrtab)) => {
+                        r.str(shstrtab.offset + section.name as usize)
+                    }
+                    _ => String::new(),
+                }
+            }
+        }
+    };
+
+    let mut symbols = vec![];
+    for symtab in sections.iter().filter(|s| s.type_ == SHT_SYMTAB) {
+        let strtab = &sections[symtab.link as usize];
+
+        // NOTE the first entry is the reserved, null symbol
+        for i in 1..symtab.size / symtab.entsize {
+            let e = symtab.offset + i * symtab.entsize;
+
+            let (name, value_size, info, shndx) = if r.is_64 {
+                (r.u32(e), r.word(e + 0x10), bytes[e + 0x04], r.u16(e + 0x06))
+            } else {
+                (r.u32(e), r.word(e + 0x08), bytes[e + 0x0c], r.u16(e + 0x0e))
+            };
+
+            let binding = match info >> 4 {
+                0 => Binding::Local,
+                1 => Binding::Global,
+                2 => Binding::Weak,
+                // e.g. `STB_GNU_UNIQUE`
+                _ => Binding::Global,
+            };
+
+            if binding == Binding::Local || shndx == SHN_UNDEF {
+                continue;
+            }
+
+            symbols.push(Symbol {
+                name: r.str(strtab.offset + name as usize),
+                binding,
+                size: value_size,
+                section: section_name(shndx),
+            });
+        }
+    }
+
+    Some(symbols)
+}
@@ -0,0 +1,69 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use gcc::Tool;
+
+use rules::Selection;
+use target::{Properties, Target};
+
+/// Renders everything that goes into building the archive: the resolved target properties, the
+/// toolchain, the compiler flags and which sources get compiled (or not, and why)
+pub fn explain(target: &Target,
+               properties: &Properties,
+               compiler: &Tool,
+               optional_flags: &[String],
+               archiver: &str,
+               patches: &[PathBuf],
+               selection: &Selection)
+               -> String {
+    let mut s = String::new();
+
+    writeln!(s, "target: {}", target.name).unwrap();
+    writeln!(s, "llvm-target: {}", target.llvm_target()).unwrap();
+    writeln!(s, "cpu: {}", target.cpu().unwrap_or("-")).unwrap();
+    writeln!(s, "features: {}", target.features().unwrap_or("-")).unwrap();
+
+    writeln!(s, "\nproperties:").unwrap();
+    writeln!(s, "    arch: {}", properties.arch).unwrap();
+    writeln!(s, "    profile: {:?}", properties.profile).unwrap();
+    writeln!(s, "    thumb: {}", properties.thumb).unwrap();
+    writeln!(s, "    fpu: {:?}", properties.fpu).unwrap();
+    writeln!(s, "    float abi: {:?}", properties.float_abi).unwrap();
+    writeln!(s, "    os: {}", properties.os).unwrap();
+    writeln!(s, "    int128: {}", properties.int128).unwrap();
+    writeln!(s, "    long double: {:?}", properties.long_double).unwrap();
+
+    writeln!(s, "\ncompiler: {}", compiler.path().display()).unwrap();
+    writeln!(s, "archiver: {}", archiver).unwrap();
+
+    writeln!(s, "\nflags:").unwrap();
+    for arg in compiler.args() {
+        writeln!(s, "    {}", arg.to_string_lossy()).unwrap();
+    }
+
+    // NOTE the compiler isn't probed in explain mode
+    writeln!(s, "\nflags, if supported:").unwrap();
+    for flag in optional_flags {
+        writeln!(s, "    {}", flag).unwrap();
+    }
+
+    // NOTE omitted when there are none so that the explanations of unpatched builds don't change
+    if !patches.is_empty() {
+        writeln!(s, "\npatches:").unwrap();
+        for patch in patches {
+            writeln!(s, "    {}", patch.display()).unwrap();
+        }
+    }
+
+    writeln!(s, "\nincluded ({}):", selection.included.len()).unwrap();
+    for source in &selection.included {
+        writeln!(s, "    {}", source).unwrap();
+    }
+
+    writeln!(s, "\nexcluded ({}):", selection.excluded.len()).unwrap();
+    for &(source, ref exclusion) in &selection.excluded {
+        writeln!(s, "    {}: {}", source, exclusion).unwrap();
+    }
+
+    s
+}
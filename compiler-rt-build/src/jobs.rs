@@ -0,0 +1,190 @@
+use std::cmp;
+use std::collections::VecDeque;
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::mem::ManuallyDrop;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A command to run and the name it's reported as
+pub struct Job {
+    pub name: String,
+    pub command: Command,
+}
+
+/// Runs the `jobs` on up to `max` threads and returns the names of the jobs that failed
+///
+/// The first thread runs on the job slot Cargo implicitly grants to every build script; each
+/// additional thread takes a token from Cargo's jobserver (if any) for every job it runs, so that
+/// the build as a whole doesn't run more than `-j` jobs at a time. The token is only requested once
+/// the thread has taken a job from the queue, so no thread waits for one when there's nothing left
+/// to run. The output of each job is printed in one piece, after the job finishes, so the
+/// diagnostics of different sources don't get interleaved.
+pub fn run(jobs: Vec<Job>, max: usize) -> Vec<String> {
+    let jobserver = Arc::new(Jobserver::from_env());
+    let threads = cmp::max(1, cmp::min(max, jobs.len()));
+    let queue = Arc::new(Mutex::new(jobs.into_iter().collect::<VecDeque<_>>()));
+    let failures = Arc::new(Mutex::new(vec![]));
+
+    let handles = (0..threads)
+        .map(|thread| {
+            let (queue, failures, jobserver) = (queue.clone(), failures.clone(), jobserver.clone());
+
+            thread::spawn(move || {
+                loop {
+                    let job = match queue.lock().unwrap().pop_front() {
+                        Some(job) => job,
+                        None => break,
+                    };
+
+                    let token = match *jobserver {
+                        Some(ref jobserver) if thread != 0 => {
+                            match jobserver.acquire() {
+                                Ok(token) => Some(token),
+                                Err(_) => {
+                                    // NOTE the job is run by another thread or, if they are all
+                                    // done, after they are joined
+                                    queue.lock().unwrap().push_front(job);
+                                    break;
+                                }
+                            }
+                        }
+                        _ => None,
+                    };
+
+                    let succeeded = run_one(job.command, &job.name);
+
+                    if let (Some(jobserver), Some(token)) = (jobserver.as_ref().as_ref(), token) {
+                        jobserver.release(token);
+                    }
+
+                    if !succeeded {
+                        failures.lock().unwrap().push(job.name);
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        try!(handle.join().map_err(|_| "a job thread panicked"));
+    }
+
+    // Jobs given back by threads that couldn't get a token run on the implicit job slot
+    for job in queue.lock().unwrap().drain(..) {
+        if !run_one(job.command, &job.name) {
+            failures.lock().unwrap().push(job.name);
+        }
+    }
+
+    let failures = failures.lock().unwrap().clone();
+    failures
+}
+
+/// Runs `command` and prints it along with its output; returns whether it succeeded
+fn run_one(mut command: Command, name: &str) -> bool {
+    match command.output() {
+        Ok(output) => {
+            let stdout = io::stdout();
+            let mut stdout = stdout.lock();
+            let _ = writeln!(stdout, "running: {:?}", command);
+            let _ = stdout.write_all(&output.stdout);
+
+            let stderr = io::stderr();
+            let mut stderr = stderr.lock();
+            let _ = stderr.write_all(&output.stderr);
+
+            output.status.success()
+        }
+        Err(e) => {
+            println!("running: {:?}", command);
+            eprintln!("couldn't run the command of {}: {}", name, e);
+            false
+        }
+    }
+}
+
+/// Client of the jobserver Cargo passes to build scripts
+///
+/// A token is a byte read from the jobserver's pipe (or FIFO) and must be written back to it once
+/// the job it was acquired for finishes.
+struct Jobserver {
+    // NOTE the descriptors are inherited from Cargo; they are not ours to close
+    read: ManuallyDrop<File>,
+    write: ManuallyDrop<File>,
+}
+
+impl Jobserver {
+    /// The jobserver described by `--jobserver-auth` in `CARGO_MAKEFLAGS`, if any
+    // NOTE `MAKEFLAGS` is ignored on purpose: `make` only passes the jobserver's descriptors to
+    // the recipes marked with `+`, so outside Cargo they may refer to some other file
+    fn from_env() -> Option<Jobserver> {
+        let flags = match env::var("CARGO_MAKEFLAGS") {
+            Ok(flags) => flags,
+            Err(_) => return None,
+        };
+
+        flags.split_whitespace()
+            .filter_map(|flag| {
+                flag.strip_prefix("--jobserver-auth=")
+                    .or_else(|| flag.strip_prefix("--jobserver-fds="))
+            })
+            .next_back()
+            .and_then(Jobserver::open)
+    }
+
+    #[cfg(unix)]
+    fn open(auth: &str) -> Option<Jobserver> {
+        use std::os::unix::io::FromRawFd;
+
+        if let Some(path) = auth.strip_prefix("fifo:") {
+            let fifo = match OpenOptions::new().read(true).write(true).open(path) {
+                Ok(fifo) => fifo,
+                Err(_) => return None,
+            };
+
+            return fifo.try_clone().ok().map(|read| {
+                Jobserver {
+                    read: ManuallyDrop::new(read),
+                    write: ManuallyDrop::new(fifo),
+                }
+            });
+        }
+
+        let fds = auth.split(',').map(|fd| fd.parse().ok()).collect::<Vec<_>>();
+        match fds[..] {
+            [Some(read), Some(write)] => unsafe {
+                Some(Jobserver {
+                    read: ManuallyDrop::new(File::from_raw_fd(read)),
+                    write: ManuallyDrop::new(File::from_raw_fd(write)),
+                })
+            },
+            _ => None,
+        }
+    }
+
+    // TODO Windows jobservers are semaphores
+    #[cfg(not(unix))]
+    fn open(_auth: &str) -> Option<Jobserver> {
+        None
+    }
+
+    /// Blocks until a token is available
+    fn acquire(&self) -> io::Result<u8> {
+        let mut token = [0];
+        loop {
+            match (&*self.read).read(&mut token) {
+                Ok(1) => return Ok(token[0]),
+                Ok(_) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "jobserver closed")),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn release(&self, token: u8) {
+        let _ = (&*self.write).write_all(&[token]);
+    }
+}
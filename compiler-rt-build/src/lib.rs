@@ -0,0 +1,648 @@
+//! Builds compiler-rt's builtins (`libcompiler-rt.a`) for a Rust target without using compiler-rt's
+//! CMake build system
+//!
+//! This is the build logic of the `compiler-rt` crate, for tools that need to build the library
+//! outside of its build script, e.g. to put it in a custom sysroot.
+//!
+//! ```no_run
+//! extern crate compiler_rt_build;
+//!
+//! use compiler_rt_build::{Build, Target};
+//!
+//! fn main() {
+//!     let output = Build::new(Target::new("cortex-m3"))
+//!         .out_dir("target/compiler-rt")
+//!         .host("x86_64-unknown-linux-gnu")
+//!         .opt_level("s")
+//!         .compile();
+//!
+//!     println!("{} defines {} symbols", output.archive.display(), output.symbols.len());
+//! }
+//! ```
+//!
+//! Like the `gcc` crate, the settings that aren't set explicitly are taken from the variables Cargo
+//! passes to build scripts (`OUT_DIR`, `HOST`, `OPT_LEVEL`, `DEBUG` and `PROFILE`), and failures
+//! are reported by panicking.
+
+extern crate gcc;
+extern crate serde_json;
+extern crate tempdir;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use gcc::Tool;
+use tempdir::TempDir;
+
+use elf::Binding;
+use rules::Selection;
+
+pub use target::{FloatAbi, Fpu, LongDouble, Profile, Properties, Target};
+pub use toolchain::fpu;
+
+macro_rules! try {
+    ($e:expr) => {
+        $e.unwrap_or_else(|e| panic!("{} with {}", stringify!($e), e))
+    }
+}
+
+mod archive;
+mod cache;
+mod elf;
+mod explain;
+mod jobs;
+mod libcalls;
+mod manifest;
+mod patches;
+mod rules;
+mod sections;
+mod sources;
+mod symbols;
+mod target;
+mod toolchain;
+
+/// What to do when the archive lacks some of the library calls LLVM may emit for the target
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MissingSymbols {
+    /// Fail the build
+    Error,
+    /// Report them in `Output::missing`
+    Warn,
+}
+
+/// Configuration of a build of compiler-rt
+pub struct Build {
+    target: Target,
+    src: Option<PathBuf>,
+    compiler: Option<PathBuf>,
+    archiver: Option<PathBuf>,
+    out_dir: Option<PathBuf>,
+    host: Option<String>,
+    opt_level: Option<String>,
+    debug: Option<bool>,
+    release: Option<bool>,
+    symbols: Option<Vec<String>>,
+    function_sections: bool,
+    missing_symbols: MissingSymbols,
+    jobs: Option<usize>,
+    patches: Option<PathBuf>,
+}
+
+impl Build {
+    pub fn new(target: Target) -> Build {
+        Build {
+            target,
+            src: None,
+            compiler: None,
+            archiver: None,
+            out_dir: None,
+            host: None,
+            opt_level: None,
+            debug: None,
+            release: None,
+            symbols: None,
+            function_sections: false,
+            missing_symbols: MissingSymbols::Error,
+            jobs: None,
+            patches: None,
+        }
+    }
+
+    pub fn target(&self) -> &Target {
+        &self.target
+    }
+
+    /// Builds the sources of this compiler-rt checkout instead of cloning compiler-rt's repository
+    pub fn src<P: AsRef<Path>>(&mut self, src: P) -> &mut Build {
+        self.src = Some(absolute(src.as_ref()));
+        self
+    }
+
+    /// C compiler to use instead of the one derived from `CC_$TARGET` or from the `linker` field of
+    /// the target specification
+    pub fn compiler<P: AsRef<Path>>(&mut self, compiler: P) -> &mut Build {
+        self.compiler = Some(compiler.as_ref().to_owned());
+        self
+    }
+
+    /// Archiver to use instead of the one derived from `AR_$TARGET` or from the `linker` field of
+    /// the target specification
+    pub fn archiver<P: AsRef<Path>>(&mut self, archiver: P) -> &mut Build {
+        self.archiver = Some(archiver.as_ref().to_owned());
+        self
+    }
+
+    /// Directory where the objects, the archive, its symbol manifest (`symbols.json`), the compiler
+    /// flags (`flags.txt`), the applied patches (`patches.txt`), the patched copy of the sources
+    /// (`patched`), compiler-rt's unit tests (`unit`) and the assembly sources rewritten by
+    /// `function_sections` (`sections`) are placed
+    pub fn out_dir<P: AsRef<Path>>(&mut self, out_dir: P) -> &mut Build {
+        self.out_dir = Some(absolute(out_dir.as_ref()));
+        self
+    }
+
+    /// Host triple; a target other than the host is cross compiled
+    pub fn host(&mut self, host: &str) -> &mut Build {
+        self.host = Some(host.to_owned());
+        self
+    }
+
+    /// Optimization level: `0`, `1`, `2`, `3`, `s` or `z`
+    pub fn opt_level(&mut self, opt_level: &str) -> &mut Build {
+        self.opt_level = Some(opt_level.to_owned());
+        self
+    }
+
+    /// Whether to generate debug info
+    pub fn debug(&mut self, debug: bool) -> &mut Build {
+        self.debug = Some(debug);
+        self
+    }
+
+    /// Whether to compile out the assertions (`-DNDEBUG`), like CMake's Release configuration
+    pub fn release(&mut self, release: bool) -> &mut Build {
+        self.release = Some(release);
+        self
+    }
+
+    /// Only build the sources that define these symbols, plus the sources they depend on
+    pub fn symbols(&mut self, symbols: Vec<String>) -> &mut Build {
+        self.symbols = Some(symbols);
+        self
+    }
+
+    /// Place each assembly routine in its own `.text.$symbol` section (ELF targets only)
+    pub fn function_sections(&mut self, function_sections: bool) -> &mut Build {
+        self.function_sections = function_sections;
+        self
+    }
+
+    pub fn missing_symbols(&mut self, missing_symbols: MissingSymbols) -> &mut Build {
+        self.missing_symbols = missing_symbols;
+        self
+    }
+
+    /// Directory with patches (`*.patch` or `*.diff` files, relative to the root of compiler-rt's
+    /// repository) to apply to the sources before compiling them, in name order
+    ///
+    /// The patches are applied to a copy of the sources in `out_dir`; the `src` checkout isn't
+    /// modified.
+    pub fn patches<P: AsRef<Path>>(&mut self, patches: P) -> &mut Build {
+        self.patches = Some(absolute(patches.as_ref()));
+        self
+    }
+
+    /// Maximum number of sources to compile at the same time
+    ///
+    /// When run by Cargo, the compilations also share the job slots of Cargo's jobserver.
+    pub fn jobs(&mut self, jobs: usize) -> &mut Build {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    /// Resolves the target, selects the sources and configures the toolchain, without compiling
+    /// anything
+    pub fn plan<'a>(&'a self) -> Plan<'a> {
+        let properties = self.target.properties();
+        let mut selection = Selection::new(&properties);
+        if let Some(ref symbols) = self.symbols {
+            selection.retain_needed(symbols, &properties);
+        }
+
+        // NOTE compiler-rt is only fetched when the plan is compiled, but the flags already depend
+        // on where it will be
+        let (checkout, td) = match self.src {
+            Some(ref src) => (src.clone(), None),
+            None => {
+                let td = try!(TempDir::new("compiler-rt"));
+                (td.path().to_owned(), Some(td))
+            }
+        };
+
+        // NOTE patched sources are compiled from a copy, so that the checkout isn't modified
+        let patches = self.patches.as_ref().map(|dir| patches::find(dir)).unwrap_or_default();
+        let src = if patches.is_empty() {
+            checkout.clone()
+        } else {
+            self.get_out_dir().join("patched")
+        };
+
+        let archiver = toolchain::archiver(self);
+        let config = toolchain::configure(self, &properties, &archiver, &src);
+        let optional_flags = toolchain::optional_flags(&src);
+
+        Plan {
+            build: self,
+            checkout,
+            src,
+            td,
+            patches,
+            properties,
+            selection,
+            config,
+            optional_flags,
+            archiver,
+        }
+    }
+
+    /// Builds the library
+    pub fn compile(&self) -> Output {
+        self.plan().compile()
+    }
+
+    fn get_out_dir(&self) -> PathBuf {
+        self.out_dir.clone().unwrap_or_else(|| PathBuf::from(try!(env::var("OUT_DIR"))))
+    }
+
+    fn get_host(&self) -> String {
+        self.host.clone().unwrap_or_else(|| try!(env::var("HOST")))
+    }
+
+    fn get_opt_level(&self) -> String {
+        self.opt_level.clone().unwrap_or_else(|| try!(env::var("OPT_LEVEL")))
+    }
+
+    fn get_debug(&self) -> bool {
+        self.debug.unwrap_or_else(|| match env::var("DEBUG") {
+            Ok(ref debug) => debug != "false" && debug != "0" && debug != "none",
+            Err(_) => false,
+        })
+    }
+
+    fn get_jobs(&self) -> usize {
+        self.jobs.unwrap_or_else(|| {
+            env::var("NUM_JOBS").ok().and_then(|jobs| jobs.parse().ok()).unwrap_or(1)
+        })
+    }
+
+    fn get_release(&self) -> bool {
+        self.release
+            .unwrap_or_else(|| env::var("PROFILE").ok().as_deref() == Some("release"))
+    }
+}
+
+/// What a `Build` is going to do
+pub struct Plan<'a> {
+    build: &'a Build,
+    /// Where compiler-rt is, or gets fetched into
+    checkout: PathBuf,
+    /// Where the sources are compiled from: the checkout or, if there are patches, a copy of it
+    src: PathBuf,
+    /// Directory compiler-rt gets fetched into, if the build has no `src`
+    td: Option<TempDir>,
+    patches: Vec<PathBuf>,
+    properties: Properties<'a>,
+    selection: Selection,
+    #[allow(deprecated)]
+    config: gcc::Build,
+    /// Flags added to `config` when compiling, if the compiler supports them
+    optional_flags: Vec<String>,
+    archiver: String,
+}
+
+impl<'a> Plan<'a> {
+    pub fn properties(&self) -> &Properties<'a> {
+        &self.properties
+    }
+
+    /// Sources (relative to `lib/builtins`) that will be compiled
+    pub fn included(&self) -> &[&'static str] {
+        &self.selection.included
+    }
+
+    /// Sources that won't be compiled and why
+    pub fn excluded(&self) -> Vec<(&'static str, String)> {
+        self.selection
+            .excluded
+            .iter()
+            .map(|&(source, ref exclusion)| (source, exclusion.to_string()))
+            .collect()
+    }
+
+    /// The compiler and the flags the sources will be compiled with, minus `optional_flags`
+    pub fn compiler(&self) -> Tool {
+        self.config.get_compiler()
+    }
+
+    /// Flags the sources will also be compiled with if the compiler supports them
+    pub fn optional_flags(&self) -> &[String] {
+        &self.optional_flags
+    }
+
+    pub fn archiver(&self) -> &str {
+        &self.archiver
+    }
+
+    /// Patches that will be applied to the sources, in order
+    pub fn patches(&self) -> &[PathBuf] {
+        &self.patches
+    }
+
+    /// Symbols the included sources define
+    pub fn symbols(&self) -> Vec<String> {
+        self.selection.included.iter().flat_map(|source| self.defined_by(source)).collect()
+    }
+
+    /// Symbols `source` defines when compiled for this target
+    pub fn defined_by(&self, source: &str) -> Vec<String> {
+        symbols::defined_by(source, &self.properties)
+    }
+
+    /// Renders the plan: the resolved target properties, the toolchain, the compiler flags and
+    /// which sources get compiled (or not, and why)
+    pub fn explain(&self) -> String {
+        explain::explain(&self.build.target,
+                         &self.properties,
+                         &self.compiler(),
+                         &self.optional_flags,
+                         &self.archiver,
+                         &self.patches,
+                         &self.selection)
+    }
+
+    /// Builds the library
+    pub fn compile(self) -> Output {
+        let build = self.build;
+        let target = &build.target;
+        let out_dir = build.get_out_dir();
+        try!(fs::create_dir_all(&out_dir));
+
+        let src = self.src.clone();
+        if let Some(ref td) = self.td {
+            fetch(td.path());
+        }
+
+        let revision = revision(&self.checkout);
+        if !self.patches.is_empty() {
+            patches::copy(&self.checkout, &src, &self.patches);
+            patches::apply(&src, &self.patches, revision.as_deref());
+        }
+
+        // NOTE Mach-O and COFF objects use different section directives
+        let function_sections = build.function_sections &&
+                                !target.llvm_target().contains("apple") &&
+                                !target.llvm_target().contains("windows");
+
+        for &(source, ref exclusion) in &self.selection.excluded {
+            println!("skipping {}: {}", source, exclusion);
+        }
+
+        // Record the patches the sources were built with
+        let names = self.patches
+            .iter()
+            .map(|patch| patch.file_name().unwrap().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        try!(try!(File::create(out_dir.join("patches.txt")))
+            .write_all(names.join("\n").as_bytes()));
+
+        // Record the flags that all the sources get compiled with
+        let mut config = self.config.clone();
+        for flag in &self.optional_flags {
+            if toolchain::supports(&config.get_compiler(), flag, &out_dir) {
+                config.flag(flag);
+            }
+        }
+        let compiler = config.get_compiler();
+        let flags = compiler.args()
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        println!("compiler: {}", compiler.path().display());
+        println!("flags: {}", flags.join(" "));
+        try!(try!(File::create(out_dir.join("flags.txt"))).write_all(flags.join("\n").as_bytes()));
+
+        // Objects left in `out_dir` by a previous build are reused if their source, the headers and
+        // the flags haven't changed since
+        let common = cache::common(&compiler, &src);
+        let mut objects = vec![];
+        let mut stale = vec![];
+        let mut jobs = vec![];
+        for source in &self.selection.included {
+            let path = src.join("lib/builtins").join(source);
+
+            // NOTE C sources are always compiled with `-ffunction-sections` and `-fdata-sections`.
+            // The assembly ones are split in a copy, so the checkout isn't modified
+            let input = if function_sections && source.ends_with(".S") {
+                let copy = out_dir.join("sections").join(source);
+                sections::split(&path, &copy);
+                copy
+            } else {
+                path
+            };
+
+            let object = out_dir.join(source).with_extension("o");
+            try!(fs::create_dir_all(object.parent().unwrap()));
+
+            let key = cache::key(&common, source, &input);
+            if key.as_ref().map(|key| cache::is_fresh(&object, key)) != Some(true) {
+                cache::forget(&object);
+                jobs.push(jobs::Job {
+                    name: source.to_string(),
+                    command: toolchain::command(&compiler, &src, source, &input, &object),
+                });
+                stale.push((*source, object.clone(), key));
+            }
+            objects.push(object);
+        }
+        println!("{} of {} objects are up to date",
+                 objects.len() - stale.len(),
+                 objects.len());
+
+        let failed = jobs::run(jobs, build.get_jobs());
+        for &(source, ref object, ref key) in &stale {
+            if let Some(ref key) = *key {
+                if !failed.iter().any(|failed| failed == source) {
+                    cache::record(object, key);
+                }
+            }
+        }
+        assert!(failed.is_empty(),
+                "{} failed to compile {}",
+                compiler.path().display(),
+                failed.join(", "));
+
+        let archive = out_dir.join("libcompiler-rt.a");
+        archive::create(&self.archiver, &archive, &objects);
+
+        // compiler-rt's own unit tests; `tests/unit.rs` runs them against native archives and
+        // `ci/qemu.sh` against ARM ones
+        copy_unit_tests(&src, &out_dir.join("unit"));
+
+        // What actually ended up in the archive
+        let manifest = out_dir.join("symbols.json");
+        let (mut symbols, missing) = match manifest::read(&archive, &self.selection.included) {
+            Some(entries) => {
+                manifest::write(&manifest, &entries);
+                audit(&entries);
+
+                // NOTE if only some symbols were requested, `retain_needed` already checked that
+                // they'll be defined
+                let missing = if build.symbols.is_none() {
+                    verify(&entries, &self.properties, &self.selection, build.missing_symbols)
+                } else {
+                    vec![]
+                };
+
+                (entries.into_iter().map(|entry| entry.symbol.name).collect::<Vec<_>>(), missing)
+            }
+            None => {
+                println!("the archive members aren't ELF objects; skipping the symbol manifest and \
+                          the checks of the archive");
+                if manifest.exists() {
+                    try!(fs::remove_file(&manifest));
+                }
+
+                (self.symbols(), vec![])
+            }
+        };
+        symbols.sort();
+        symbols.dedup();
+
+        Output {
+            archive,
+            compiled: stale.into_iter().map(|(source, _, _)| source).collect(),
+            compiler: compiler.path().to_owned(),
+            excluded: self.excluded(),
+            flags,
+            included: self.selection.included,
+            missing,
+            patches: self.patches,
+            revision,
+            symbols,
+        }
+    }
+}
+
+/// Outcome of a build
+pub struct Output {
+    /// `libcompiler-rt.a`
+    pub archive: PathBuf,
+    pub compiler: PathBuf,
+    /// Flags every source was compiled with
+    pub flags: Vec<String>,
+    /// Sources (relative to `lib/builtins`) that the archive was built from
+    pub included: Vec<&'static str>,
+    /// Sources that this build (re)compiled; the objects of the other included sources were up to
+    /// date
+    pub compiled: Vec<&'static str>,
+    /// Sources that weren't compiled and why
+    pub excluded: Vec<(&'static str, String)>,
+    /// Symbols the archive defines, sorted
+    ///
+    /// If the archive members aren't ELF objects, these are the symbols the included sources are
+    /// expected to define instead.
+    pub symbols: Vec<String>,
+    /// Commit hash of the compiler-rt sources, if they are a git checkout
+    pub revision: Option<String>,
+    /// Patches that were applied to the sources (or that they already had), in order
+    pub patches: Vec<PathBuf>,
+    /// Why the library calls LLVM may emit for the target, but that the archive lacks, are missing
+    /// (only with `MissingSymbols::Warn`)
+    pub missing: Vec<String>,
+}
+
+/// `path` relative to the current directory; the sources are compiled from within the checkout
+fn absolute(path: &Path) -> PathBuf {
+    try!(env::current_dir()).join(path)
+}
+
+fn fetch(td: &Path) {
+    // FIXME use the `curl`, `flate2`, `tar` crates instead of shelling out to `git`.
+    // FIXME Should probably use the rust-lang/compiler-rt repository
+    assert!(try!(Command::new("git")
+            .args(["clone", "--depth", "1", "https://github.com/llvm-mirror/compiler-rt"])
+            .arg(td)
+            .status())
+        .success());
+}
+
+/// Copies the `test/builtins/Unit/*_test.c` files and the headers they include into `dst`
+fn copy_unit_tests(src: &Path, dst: &Path) {
+    // Don't keep the tests of a previous checkout around
+    if dst.exists() {
+        try!(fs::remove_dir_all(dst));
+    }
+
+    let include = dst.join("include");
+    try!(fs::create_dir_all(&include));
+
+    for &(dir, suffix, dst) in &[("test/builtins/Unit", "_test.c", dst),
+                                 ("lib/builtins", ".h", &*include)] {
+        let entries = match fs::read_dir(src.join(dir)) {
+            Ok(entries) => entries,
+            // Older checkouts have no unit tests
+            Err(_) => continue,
+        };
+
+        for entry in entries {
+            let path = try!(entry).path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+
+            if name.ends_with(suffix) {
+                try!(fs::copy(&path, dst.join(name)));
+            }
+        }
+    }
+}
+
+/// Reports the symbols that more than one archive member defines
+///
+/// Weak definitions are meant to be overridden (the linker picks the strong one, if any) but two
+/// strong definitions of the same symbol are a conflict and fail the build
+fn audit(entries: &[manifest::Entry]) {
+    let mut conflicts = vec![];
+    for duplicates in manifest::duplicates(entries) {
+        let sources = duplicates.iter()
+            .map(|entry| format!("{} ({})", entry.source, entry.symbol.binding.name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let report = format!("`{}` is defined by {}", duplicates[0].symbol.name, sources);
+
+        if duplicates.iter().filter(|entry| entry.symbol.binding == Binding::Global).count() > 1 {
+            conflicts.push(report);
+        } else {
+            println!("weak duplicate: {}", report);
+        }
+    }
+
+    assert!(conflicts.is_empty(),
+            "the archive contains conflicting definitions:\n{}",
+            conflicts.join("\n"));
+}
+
+/// Checks that the archive defines all the library calls LLVM may emit for the target and returns
+/// the reasons why the ones it doesn't define are missing
+fn verify(entries: &[manifest::Entry],
+          properties: &Properties,
+          selection: &Selection,
+          missing_symbols: MissingSymbols)
+          -> Vec<String> {
+    let missing = libcalls::required(properties)
+        .into_iter()
+        .filter(|symbol| !entries.iter().any(|entry| entry.symbol.name == *symbol))
+        .map(|symbol| selection.why_undefined(symbol, properties))
+        .collect::<Vec<_>>();
+
+    if missing_symbols == MissingSymbols::Error && !missing.is_empty() {
+        panic!("the archive doesn't define {} intrinsic(s) that LLVM may call:\n{}",
+               missing.len(),
+               missing.join("\n"))
+    }
+
+    missing
+}
+
+/// Commit hash of the compiler-rt sources
+fn revision(src: &Path) -> Option<String> {
+    if !src.join(".git").exists() {
+        return None;
+    }
+
+    let output = try!(Command::new("git").args(["rev-parse", "HEAD"]).current_dir(src).output());
+    assert!(output.status.success());
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
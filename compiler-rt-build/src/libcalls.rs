@@ -0,0 +1,65 @@
+use rules::Requires;
+use target::{Fpu, Profile, Properties};
+
+/// Library calls LLVM emits when lowering code for targets that meet *all* the `requires`ments
+pub struct Libcalls {
+    pub requires: &'static [Requires],
+    pub symbols: &'static [&'static str],
+}
+
+// NOTE not exhaustive: these are the calls that plain Rust code (integer division, floating point
+// arithmetic and conversions, `powi`, `copy_nonoverlapping`, etc.) ends up lowered to
+pub const LIBCALLS: &[Libcalls] =
+    &[Libcalls {
+          requires: &[],
+          symbols: &["__powidf2", "__powisf2"],
+      },
+      // ARM EABI run-time helpers (see "Run-time ABI for the ARM Architecture")
+      Libcalls {
+          requires: &[Requires::Arch("arm")],
+          symbols: &["__aeabi_d2lz", "__aeabi_d2ulz", "__aeabi_f2lz", "__aeabi_f2ulz",
+                     "__aeabi_l2d", "__aeabi_l2f", "__aeabi_ldivmod", "__aeabi_memclr",
+                     "__aeabi_memclr4", "__aeabi_memclr8", "__aeabi_memcpy", "__aeabi_memcpy4",
+                     "__aeabi_memcpy8", "__aeabi_memmove", "__aeabi_memmove4",
+                     "__aeabi_memmove8", "__aeabi_memset", "__aeabi_memset4", "__aeabi_memset8",
+                     "__aeabi_ul2d", "__aeabi_ul2f", "__aeabi_uldivmod"],
+      },
+      // ARMv6-M has neither hardware division nor a 32x32 -> 64 multiplication
+      Libcalls {
+          requires: &[Requires::Arch("arm"), Requires::Profile(Profile::V6m)],
+          symbols: &["__aeabi_idiv", "__aeabi_idivmod", "__aeabi_lmul", "__aeabi_uidiv",
+                     "__aeabi_uidivmod"],
+      },
+      Libcalls {
+          requires: &[Requires::Arch("arm"), Requires::Not(&Requires::Fpu(Fpu::Single))],
+          symbols: &["__aeabi_f2iz", "__aeabi_f2uiz", "__aeabi_fadd", "__aeabi_fcmpeq",
+                     "__aeabi_fcmpge", "__aeabi_fcmpgt", "__aeabi_fcmple", "__aeabi_fcmplt",
+                     "__aeabi_fcmpun", "__aeabi_fdiv", "__aeabi_fmul", "__aeabi_fsub",
+                     "__aeabi_i2f", "__aeabi_ui2f"],
+      },
+      Libcalls {
+          requires: &[Requires::Arch("arm"), Requires::Not(&Requires::Fpu(Fpu::Double))],
+          symbols: &["__aeabi_d2f", "__aeabi_d2iz", "__aeabi_d2uiz", "__aeabi_dadd",
+                     "__aeabi_dcmpeq", "__aeabi_dcmpge", "__aeabi_dcmpgt", "__aeabi_dcmple",
+                     "__aeabi_dcmplt", "__aeabi_dcmpun", "__aeabi_ddiv", "__aeabi_dmul",
+                     "__aeabi_dsub", "__aeabi_f2d", "__aeabi_i2d", "__aeabi_ui2d"],
+      },
+      // 64-bit division on other 32-bit architectures
+      Libcalls {
+          requires: &[Requires::Not(&Requires::Arch("arm")), Requires::Not(&Requires::Int128)],
+          symbols: &["__divdi3", "__moddi3", "__udivdi3", "__umoddi3"],
+      },
+      Libcalls {
+          requires: &[Requires::Int128],
+          symbols: &["__divti3", "__fixdfti", "__fixsfti", "__fixunsdfti", "__fixunssfti",
+                     "__floattidf", "__floattisf", "__floatuntidf", "__floatuntisf", "__modti3",
+                     "__muloti4", "__udivti3", "__umodti3"],
+      }];
+
+/// Library calls LLVM may emit for a target with these `properties`
+pub fn required(properties: &Properties) -> Vec<&'static str> {
+    LIBCALLS.iter()
+        .filter(|libcalls| libcalls.requires.iter().all(|r| r.is_met_by(properties)))
+        .flat_map(|libcalls| libcalls.symbols.iter().cloned())
+        .collect()
+}
@@ -0,0 +1,228 @@
+//! Builds `libcompiler-rt.a` for a target outside of Cargo and, optionally, installs it into a
+//! `rustc` sysroot
+
+extern crate compiler_rt_build;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::thread;
+
+use compiler_rt_build::{Build, MissingSymbols, Target};
+
+macro_rules! try {
+    ($e:expr) => {
+        $e.unwrap_or_else(|e| panic!("{} with {}", stringify!($e), e))
+    }
+}
+
+const USAGE: &str = "\
+Builds compiler-rt's builtins (libcompiler-rt.a) for a Rust target
+
+Usage: compiler-rt-build [options] <target>
+
+<target> is the name of a target, whose specification file is looked up in the current directory
+and in $RUST_TARGET_PATH, or the path to a specification file (`*.json`)
+
+Options:
+    --src <dir>                 Build this compiler-rt checkout instead of a fresh clone
+    --patches <dir>             Apply the *.patch and *.diff files in <dir> to the sources first
+    --out-dir <dir>             Where to place the archive [default: target/compiler-rt/<target>]
+    --sysroot <dir>             Also install the archive in <dir>/lib/rustlib/<target>/lib
+    --cc <compiler>             C compiler [default: $CC_<target>, or derived from the `linker` field]
+    --ar <archiver>             Archiver [default: $AR_<target>, or derived from the `linker` field]
+    --host <triple>             Host triple [default: the host of `rustc`]
+    --opt-level <level>         0, 1, 2, 3, s or z [default: 2]
+    --debug                     Generate debug info
+    --symbols <symbols>         Only build the intrinsics that define these (comma separated) symbols
+    --function-sections         Place each assembly routine in its own section (ELF only)
+    --missing-symbols <action>  warn or error [default: error]
+    -j, --jobs <n>              Compile up to <n> sources at a time [default: the number of CPUs]
+    --explain                   Print what would be built and how, but don't build anything
+    -h, --help                  Print this message
+";
+
+/// Parsed command line
+struct Args {
+    target: String,
+    src: Option<String>,
+    patches: Option<String>,
+    out_dir: Option<String>,
+    sysroot: Option<String>,
+    cc: Option<String>,
+    ar: Option<String>,
+    host: Option<String>,
+    opt_level: String,
+    debug: bool,
+    symbols: Option<String>,
+    function_sections: bool,
+    missing_symbols: MissingSymbols,
+    jobs: Option<usize>,
+    explain: bool,
+}
+
+fn main() {
+    let args = parse(env::args().skip(1).collect());
+
+    let target = if args.target.ends_with(".json") {
+        Target::from_spec(Path::new(&args.target))
+    } else {
+        Target::new(&args.target)
+    };
+    let name = target.name.clone();
+    let out_dir = args.out_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new("target/compiler-rt").join(&name));
+
+    let mut build = Build::new(target);
+    build.out_dir(&out_dir)
+        .host(&args.host.unwrap_or_else(host))
+        .opt_level(&args.opt_level)
+        .debug(args.debug)
+        .release(!args.debug)
+        .function_sections(args.function_sections)
+        .missing_symbols(args.missing_symbols)
+        .jobs(args.jobs.unwrap_or_else(|| {
+            thread::available_parallelism().map(|jobs| jobs.get()).unwrap_or(1)
+        }));
+    if let Some(ref src) = args.src {
+        build.src(src);
+    }
+    if let Some(ref patches) = args.patches {
+        build.patches(patches);
+    }
+    if let Some(ref cc) = args.cc {
+        build.compiler(cc);
+    }
+    if let Some(ref ar) = args.ar {
+        build.archiver(ar);
+    }
+    if let Some(ref symbols) = args.symbols {
+        build.symbols(symbols.split(',')
+            .map(|symbol| symbol.trim())
+            .filter(|symbol| !symbol.is_empty())
+            .map(String::from)
+            .collect());
+    }
+
+    let plan = build.plan();
+    if args.explain {
+        print!("{}", plan.explain());
+        return;
+    }
+
+    let output = plan.compile();
+    for reason in &output.missing {
+        eprintln!("warning: missing intrinsic: {}", reason);
+    }
+    for patch in &output.patches {
+        eprintln!("patched with {}", patch.display());
+    }
+    eprintln!("built {} ({} symbols)", output.archive.display(), output.symbols.len());
+
+    if let Some(sysroot) = args.sysroot {
+        let dir = Path::new(&sysroot).join("lib/rustlib").join(&name).join("lib");
+        let dst = dir.join("libcompiler-rt.a");
+
+        try!(fs::create_dir_all(&dir));
+        try!(fs::copy(&output.archive, &dst));
+        eprintln!("installed {}", dst.display());
+    }
+}
+
+/// Parses the command line arguments, exiting on errors and on `--help`
+fn parse(args: Vec<String>) -> Args {
+    let mut parsed = Args {
+        target: String::new(),
+        src: None,
+        patches: None,
+        out_dir: None,
+        sysroot: None,
+        cc: None,
+        ar: None,
+        host: None,
+        opt_level: String::from("2"),
+        debug: false,
+        symbols: None,
+        function_sections: false,
+        missing_symbols: MissingSymbols::Error,
+        jobs: None,
+        explain: false,
+    };
+    let mut target = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        {
+            let mut value = || {
+                args.next().unwrap_or_else(|| fail(&format!("{} requires a value", arg)))
+            };
+
+            match &*arg {
+                "-h" | "--help" => {
+                    print!("{}", USAGE);
+                    process::exit(0)
+                }
+                "--src" => parsed.src = Some(value()),
+                "--patches" => parsed.patches = Some(value()),
+                "--out-dir" => parsed.out_dir = Some(value()),
+                "--sysroot" => parsed.sysroot = Some(value()),
+                "--cc" => parsed.cc = Some(value()),
+                "--ar" => parsed.ar = Some(value()),
+                "--host" => parsed.host = Some(value()),
+                "--opt-level" => {
+                    parsed.opt_level = value();
+                    if !["0", "1", "2", "3", "s", "z"].contains(&&*parsed.opt_level) {
+                        fail(&format!("invalid optimization level `{}`", parsed.opt_level))
+                    }
+                }
+                "--debug" => parsed.debug = true,
+                "--symbols" => parsed.symbols = Some(value()),
+                "--function-sections" => parsed.function_sections = true,
+                "--missing-symbols" => {
+                    parsed.missing_symbols = match &*value() {
+                        "warn" => MissingSymbols::Warn,
+                        "error" => MissingSymbols::Error,
+                        other => fail(&format!("--missing-symbols must be `warn` or `error`, not \
+                                                `{}`",
+                                               other)),
+                    }
+                }
+                "-j" | "--jobs" => {
+                    let jobs = value();
+                    parsed.jobs = match jobs.parse() {
+                        Ok(0) | Err(_) => fail(&format!("invalid number of jobs `{}`", jobs)),
+                        Ok(jobs) => Some(jobs),
+                    }
+                }
+                "--explain" => parsed.explain = true,
+                _ if arg.starts_with('-') => fail(&format!("unknown option `{}`", arg)),
+                _ if target.is_some() => fail("more than one target was given"),
+                _ => target = Some(arg.clone()),
+            }
+        }
+    }
+
+    parsed.target = target.unwrap_or_else(|| fail("no target was given"));
+    parsed
+}
+
+/// Host triple of `rustc`
+fn host() -> String {
+    let output = try!(Command::new(env::var("RUSTC").unwrap_or_else(|_| String::from("rustc")))
+        .arg("-vV")
+        .output());
+    assert!(output.status.success());
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.starts_with("host: "))
+        .map(|line| line["host: ".len()..].to_owned())
+        .unwrap_or_else(|| fail("couldn't determine the host triple; use --host"))
+}
+
+fn fail(msg: &str) -> ! {
+    eprintln!("error: {}\n\n{}", msg, USAGE);
+    process::exit(1)
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use serde_json::{self, Value};
+
+use archive;
+use elf::{self, Symbol};
+
+/// A symbol defined by the archive
+pub struct Entry {
+    pub symbol: Symbol,
+    /// The compiler-rt source the member that defines the symbol was compiled from
+    pub source: &'static str,
+}
+
+/// Lists the global and weak symbols defined by the members of the `archive`, which were compiled
+/// from the `sources`
+///
+/// Returns `None` if the archive or its members are in a format that can't be read, i.e. they
+/// aren't ELF objects.
+pub fn read(archive: &Path, sources: &[&'static str]) -> Option<Vec<Entry>> {
+    let bytes = &mut vec![];
+    try!(try!(File::open(archive)).read_to_end(bytes));
+
+    archive::members(bytes).and_then(|members| {
+        let mut entries = vec![];
+        for member in members {
+            let source = *sources.iter()
+                .find(|source| {
+                    let object = Path::new(source).with_extension("o");
+                    object.file_name().and_then(|name| name.to_str()) == Some(&*member.name)
+                })
+                .unwrap_or_else(|| panic!("{} doesn't come from any compiled source", member.name));
+
+            match elf::defined_symbols(member.data) {
+                Some(symbols) => {
+                    entries.extend(symbols.into_iter().map(|symbol| {
+                        Entry {
+                            symbol,
+                            source,
+                        }
+                    }))
+                }
+                None => return None,
+            }
+        }
+
+        Some(entries)
+    })
+}
+
+/// Writes the `entries` to `path` as a JSON array
+pub fn write(path: &Path, entries: &[Entry]) {
+    let entries = entries.iter()
+        .map(|entry| {
+            let mut object = BTreeMap::new();
+            object.insert(String::from("name"), Value::String(entry.symbol.name.clone()));
+            object.insert(String::from("binding"),
+                          Value::String(String::from(entry.symbol.binding.name())));
+            object.insert(String::from("size"), Value::U64(entry.symbol.size));
+            object.insert(String::from("section"), Value::String(entry.symbol.section.clone()));
+            object.insert(String::from("source"), Value::String(String::from(entry.source)));
+            Value::Object(object)
+        })
+        .collect();
+
+    let json = try!(serde_json::to_string_pretty(&Value::Array(entries)));
+    try!(try!(File::create(path)).write_all(json.as_bytes()));
+}
+
+/// Groups the `entries` of the symbols that are defined by more than one archive member
+pub fn duplicates(entries: &[Entry]) -> Vec<Vec<&Entry>> {
+    let mut by_name = BTreeMap::new();
+    for entry in entries {
+        by_name.entry(&*entry.symbol.name).or_insert_with(Vec::new).push(entry);
+    }
+
+    by_name.into_values().filter(|entries| entries.len() > 1).collect()
+}
@@ -0,0 +1,172 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use tempdir::TempDir;
+
+/// The patches in `dir`, in the order they are applied: the `*.patch` and `*.diff` files, sorted by
+/// name
+pub fn find(dir: &Path) -> Vec<PathBuf> {
+    let mut patches = try!(fs::read_dir(dir))
+        .map(|entry| try!(entry).path())
+        .filter(|path| {
+            path.is_file() &&
+            path.extension().map(|ext| ext == "patch" || ext == "diff") == Some(true)
+        })
+        .collect::<Vec<_>>();
+    patches.sort();
+    patches
+}
+
+/// Directories of the checkout that the build reads: the sources and their headers, and the unit
+/// tests
+const COPIED: &[&str] = &["lib/builtins", "test/builtins"];
+
+/// Copies the parts of the `checkout` that the build reads, and the other files the `patches`
+/// touch, to `dst`, where the patches get applied so that the checkout isn't modified
+pub fn copy(checkout: &Path, dst: &Path, patches: &[PathBuf]) {
+    // Start over; the previous build may have applied another series
+    if dst.exists() {
+        try!(fs::remove_dir_all(dst));
+    }
+
+    for dir in COPIED {
+        copy_dir(&checkout.join(dir), &dst.join(dir));
+    }
+    for patch in patches {
+        for file in touched(patch) {
+            copy_file(&checkout.join(&file), &dst.join(&file));
+        }
+    }
+}
+
+/// Applies the `patches`, which are relative to the root of compiler-rt's repository, to the `src`
+/// copy of the sources
+///
+/// The patches the sources already have, e.g. because they were applied to the checkout by hand,
+/// are skipped.
+pub fn apply(src: &Path, patches: &[PathBuf], revision: Option<&str>) {
+    // NOTE the series is applied in order, so the sources can only have a prefix of it
+    let (had, pending) = patches.split_at((0..patches.len() + 1)
+        .rev()
+        .find(|&n| applied(src, &patches[..n]))
+        .unwrap());
+
+    for patch in had {
+        println!("patch {} is already applied", patch.display());
+    }
+
+    for (i, patch) in pending.iter().enumerate() {
+        println!("applying patch {}", patch.display());
+        if let Err(e) = git_apply(src, patch, &[]) {
+            fail(patch, had, &pending[..i], revision, &e)
+        }
+    }
+}
+
+/// Whether the `src` sources already have the `patches`
+///
+/// The patches are reverted, from last to first, in a scratch copy of the files they touch.
+// NOTE `git apply --check` can't tell: it checks each of several patches against the original
+// files, so it rejects series where a patch modifies the lines of a previous one
+fn applied(src: &Path, patches: &[PathBuf]) -> bool {
+    if patches.is_empty() {
+        return true;
+    }
+
+    let scratch = try!(TempDir::new("compiler-rt-patches"));
+    for patch in patches {
+        for file in touched(patch) {
+            copy_file(&src.join(&file), &scratch.path().join(&file));
+        }
+    }
+
+    patches.iter().rev().all(|patch| git_apply(scratch.path(), patch, &["--reverse"]).is_ok())
+}
+
+/// The files a unified diff touches, relative to the root of the repository
+fn touched(patch: &Path) -> Vec<String> {
+    let contents = &mut vec![];
+    try!(try!(File::open(patch)).read_to_end(contents));
+
+    String::from_utf8_lossy(contents)
+        .lines()
+        .filter(|line| line.starts_with("--- ") || line.starts_with("+++ "))
+        .map(|line| line[4..].split('\t').next().unwrap().trim())
+        .filter(|path| *path != "/dev/null")
+        // NOTE `git apply` strips the first component (`-p1`), i.e. the `a/` and `b/` prefixes
+        .filter_map(|path| path.find('/').map(|i| path[i + 1..].to_owned()))
+        .collect()
+}
+
+/// Copies the `from` directory, if there's one, to `to`
+// NOTE older checkouts have no unit tests
+fn copy_dir(from: &Path, to: &Path) {
+    if !from.is_dir() {
+        return;
+    }
+
+    for entry in try!(fs::read_dir(from)) {
+        let path = try!(entry).path();
+        let to = to.join(path.file_name().unwrap());
+        if path.is_dir() {
+            copy_dir(&path, &to);
+        } else {
+            copy_file(&path, &to);
+        }
+    }
+}
+
+/// Copies the `from` file, if there's one (patches can create files), to `to`
+fn copy_file(from: &Path, to: &Path) {
+    if from.is_file() {
+        try!(fs::create_dir_all(to.parent().unwrap()));
+        try!(fs::copy(from, to));
+    }
+}
+
+/// Runs `git apply $args $patch` in the `src` directory; returns the error output on failure
+///
+/// NOTE `git apply` applies all the hunks of the patch or none of them
+fn git_apply(src: &Path, patch: &Path, args: &[&str]) -> Result<(), String> {
+    let output = try!(Command::new("git")
+        .current_dir(src)
+        // Don't look for a repository above `src`; in a subdirectory of a repository, `git apply`
+        // ignores the changes to the files outside of that subdirectory
+        .env("GIT_CEILING_DIRECTORIES", src.parent().unwrap_or(src))
+        .arg("apply")
+        .args(args)
+        .arg(patch)
+        .output());
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
+
+/// Reports that `patch` conflicts with the sources, which already `had` some patches of the series
+/// and to which this build `applied` some others
+fn fail(patch: &Path, had: &[PathBuf], applied: &[PathBuf], revision: Option<&str>, error: &str) -> ! {
+    let list = |patches: &[PathBuf]| {
+        patches.iter()
+            .map(|patch| patch.file_name().unwrap().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut report = format!("patch {} doesn't apply to the compiler-rt sources{}",
+                             patch.display(),
+                             revision.map(|revision| format!(" (revision {})", revision))
+                                 .unwrap_or_default());
+    if !had.is_empty() {
+        report.push_str(&format!("\nthe sources already had: {}", list(had)));
+    }
+    if !applied.is_empty() {
+        report.push_str(&format!("\napplied by this build: {}", list(applied)));
+    }
+
+    panic!("{}:\n{}", report, error)
+}
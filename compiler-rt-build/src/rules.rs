@@ -0,0 +1,360 @@
+use std::fmt;
+use std::path::Path;
+
+use sources::{ARM_SOURCES, GENERIC_SOURCES};
+use symbols;
+use target::{Fpu, LongDouble, Profile, Properties};
+
+/// A property a target must have
+#[derive(Debug)]
+pub enum Requires {
+    Arch(&'static str),
+    /// Code generated in ARM state, i.e. not in Thumb state
+    ArmState,
+    /// Any FPU that supports at least this precision
+    Fpu(Fpu),
+    Int128,
+    LongDouble(LongDouble),
+    /// Any OS but this one
+    NotOs(&'static str),
+    /// Any ISA profile but this one
+    NotProfile(Profile),
+    Profile(Profile),
+    /// The opposite requirement
+    Not(&'static Requires),
+}
+
+impl Requires {
+    pub fn is_met_by(&self, properties: &Properties) -> bool {
+        match *self {
+            Requires::Arch(arch) => properties.arch == arch,
+            Requires::ArmState => !properties.thumb,
+            Requires::Fpu(fpu) => properties.fpu >= fpu,
+            Requires::Int128 => properties.int128,
+            Requires::LongDouble(format) => properties.long_double == format,
+            Requires::NotOs(os) => properties.os != os,
+            Requires::NotProfile(profile) => properties.profile != profile,
+            Requires::Profile(profile) => properties.profile == profile,
+            Requires::Not(requires) => !requires.is_met_by(properties),
+        }
+    }
+}
+
+impl fmt::Display for Requires {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Requires::Arch(arch) => write!(f, "arch = {}", arch),
+            Requires::ArmState => f.write_str("ARM state"),
+            Requires::Fpu(fpu) => write!(f, "fpu >= {:?}", fpu),
+            Requires::Int128 => f.write_str("128-bit integers"),
+            Requires::LongDouble(format) => write!(f, "long double = {:?}", format),
+            Requires::NotOs(os) => write!(f, "os != {}", os),
+            Requires::NotProfile(profile) => write!(f, "profile != {:?}", profile),
+            Requires::Profile(profile) => write!(f, "profile = {:?}", profile),
+            Requires::Not(requires) => write!(f, "not ({})", requires),
+        }
+    }
+}
+
+/// `sources` are only compiled for targets that meet the `requires`ment
+pub struct Rule {
+    /// Name used when reporting why a source was excluded
+    pub name: &'static str,
+    pub requires: Requires,
+    pub sources: &'static [&'static str],
+}
+
+pub const RULES: &[Rule] = &[Rule {
+                                 name: "arm",
+                                 requires: Requires::Arch("arm"),
+                                 sources: ARM_SOURCES,
+                             },
+                             // NOTE These asm implementations only work in ARM mode. IOW,
+                             // these don't work in THUMB mode.
+                             Rule {
+                                 name: "arm-state",
+                                 requires: Requires::ArmState,
+                                 sources: &["arm/aeabi_cdcmp.S",
+                                            "arm/aeabi_cfcmp.S",
+                                            "arm/eqdf2vfp.S",
+                                            "arm/eqsf2vfp.S",
+                                            "arm/gedf2vfp.S",
+                                            "arm/gesf2vfp.S",
+                                            "arm/gtdf2vfp.S",
+                                            "arm/gtsf2vfp.S",
+                                            "arm/ledf2vfp.S",
+                                            "arm/lesf2vfp.S",
+                                            "arm/ltdf2vfp.S",
+                                            "arm/ltsf2vfp.S",
+                                            "arm/nedf2vfp.S",
+                                            "arm/nesf2vfp.S",
+                                            "arm/unorddf2vfp.S",
+                                            "arm/unordsf2vfp.S"],
+                             },
+                             // NOTE These asm implementations use instructions that ARMv6-M
+                             // doesn't have
+                             Rule {
+                                 name: "not-armv6m",
+                                 requires: Requires::NotProfile(Profile::V6m),
+                                 sources: &["arm/aeabi_dcmp.S",
+                                            "arm/aeabi_fcmp.S",
+                                            "arm/aeabi_ldivmod.S",
+                                            "arm/aeabi_uldivmod.S",
+                                            "arm/clzdi2.S",
+                                            "arm/clzsi2.S",
+                                            "arm/comparesf2.S",
+                                            "arm/divmodsi4.S",
+                                            "arm/divsi3.S",
+                                            "arm/modsi3.S",
+                                            "arm/negdf2vfp.S",
+                                            "arm/negsf2vfp.S",
+                                            "arm/switch16.S",
+                                            "arm/switch32.S",
+                                            "arm/switch8.S",
+                                            "arm/switchu8.S",
+                                            "arm/sync_fetch_and_add_4.S",
+                                            "arm/sync_fetch_and_and_4.S",
+                                            "arm/sync_fetch_and_max_4.S",
+                                            "arm/sync_fetch_and_min_4.S",
+                                            "arm/sync_fetch_and_nand_4.S",
+                                            "arm/sync_fetch_and_or_4.S",
+                                            "arm/sync_fetch_and_sub_4.S",
+                                            "arm/sync_fetch_and_umax_4.S",
+                                            "arm/sync_fetch_and_umin_4.S",
+                                            "arm/sync_fetch_and_xor_4.S",
+                                            "arm/udivmodsi4.S",
+                                            "arm/udivsi3.S",
+                                            "arm/umodsi3.S"],
+                             },
+                             Rule {
+                                 name: "os",
+                                 requires: Requires::NotOs("none"),
+                                 sources: &["enable_execute_stack.c"],
+                             },
+                             Rule {
+                                 name: "vfp",
+                                 requires: Requires::Fpu(Fpu::Single),
+                                 sources: &["arm/adddf3vfp.S",
+                                            "arm/addsf3vfp.S",
+                                            "arm/divdf3vfp.S",
+                                            "arm/divsf3vfp.S",
+                                            "arm/eqdf2vfp.S",
+                                            "arm/extendsfdf2vfp.S",
+                                            "arm/fixdfsivfp.S",
+                                            "arm/fixsfsivfp.S",
+                                            "arm/fixunsdfsivfp.S",
+                                            "arm/fixunssfsivfp.S",
+                                            "arm/floatsidfvfp.S",
+                                            "arm/floatsisfvfp.S",
+                                            "arm/floatunssidfvfp.S",
+                                            "arm/floatunssisfvfp.S",
+                                            "arm/gedf2vfp.S",
+                                            "arm/gtdf2vfp.S",
+                                            "arm/ledf2vfp.S",
+                                            "arm/ltdf2vfp.S",
+                                            "arm/ltsf2vfp.S",
+                                            "arm/muldf3vfp.S",
+                                            "arm/mulsf3vfp.S",
+                                            "arm/nedf2vfp.S",
+                                            "arm/nesf2vfp.S",
+                                            "arm/restore_vfp_d8_d15_regs.S",
+                                            "arm/save_vfp_d8_d15_regs.S",
+                                            "arm/subdf3vfp.S",
+                                            "arm/subsf3vfp.S",
+                                            "arm/truncdfsf2vfp.S",
+                                            "arm/unorddf2vfp.S",
+                                            "arm/unordsf2vfp.S"],
+                             },
+                             // NOTE these intrinsics require a DP FPU
+                             Rule {
+                                 name: "vfp-dp",
+                                 requires: Requires::Fpu(Fpu::Double),
+                                 sources: &["arm/adddf3vfp.S",
+                                            "arm/divdf3vfp.S",
+                                            "arm/extendsfdf2vfp.S",
+                                            "arm/fixdfsivfp.S",
+                                            "arm/fixunsdfsivfp.S",
+                                            "arm/floatsidfvfp.S",
+                                            "arm/floatunssidfvfp.S",
+                                            "arm/muldf3vfp.S",
+                                            "arm/subdf3vfp.S",
+                                            "arm/truncdfsf2vfp.S"],
+                             },
+                             // NOTE these compile to empty objects unless
+                             // `CRT_HAS_128BIT` is defined
+                             Rule {
+                                 name: "int128",
+                                 requires: Requires::Int128,
+                                 sources: &["absvti2.c",
+                                            "addvti3.c",
+                                            "ashlti3.c",
+                                            "ashrti3.c",
+                                            "clzti2.c",
+                                            "cmpti2.c",
+                                            "ctzti2.c",
+                                            "divti3.c",
+                                            "ffsti2.c",
+                                            "fixdfti.c",
+                                            "fixsfti.c",
+                                            "fixunsdfti.c",
+                                            "fixunssfti.c",
+                                            "fixunsxfti.c",
+                                            "fixxfti.c",
+                                            "floattidf.c",
+                                            "floattisf.c",
+                                            "floattixf.c",
+                                            "floatuntidf.c",
+                                            "floatuntisf.c",
+                                            "floatuntixf.c",
+                                            "lshrti3.c",
+                                            "modti3.c",
+                                            "muloti4.c",
+                                            "multi3.c",
+                                            "mulvti3.c",
+                                            "negti2.c",
+                                            "negvti2.c",
+                                            "parityti2.c",
+                                            "popcountti2.c",
+                                            "subvti3.c",
+                                            "ucmpti2.c",
+                                            "udivmodti4.c",
+                                            "udivti3.c",
+                                            "umodti3.c"],
+                             },
+                             // NOTE these compile to empty objects unless
+                             // `CRT_LDBL_128BIT` is defined
+                             Rule {
+                                 name: "binary128",
+                                 requires: Requires::LongDouble(LongDouble::Binary128),
+                                 sources: &["addtf3.c",
+                                            "divtf3.c",
+                                            "multf3.c",
+                                            "powitf2.c",
+                                            "subtf3.c"],
+                             }];
+
+/// Why a source won't be compiled
+pub enum Exclusion {
+    /// The target doesn't meet the requirement of this rule
+    Rule(&'static Rule),
+    /// This arch-specific source will be compiled instead
+    ReplacedBy(&'static str),
+    /// None of the requested symbols needs this source
+    NotNeeded,
+}
+
+impl fmt::Display for Exclusion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Exclusion::Rule(rule) => write!(f, "rule `{}` requires {}", rule.name, rule.requires),
+            Exclusion::ReplacedBy(source) => write!(f, "replaced by {}", source),
+            Exclusion::NotNeeded => f.write_str("not needed by the requested symbols"),
+        }
+    }
+}
+
+/// Outcome of evaluating the `RULES` against a target
+pub struct Selection {
+    /// Sources to compile
+    pub included: Vec<&'static str>,
+    /// Sources that won't be compiled and why
+    pub excluded: Vec<(&'static str, Exclusion)>,
+}
+
+impl Selection {
+    pub fn new(properties: &Properties) -> Self {
+        let mut included = vec![];
+        let mut excluded = vec![];
+
+        for source in GENERIC_SOURCES.iter().chain(ARM_SOURCES) {
+            match RULES.iter()
+                .find(|rule| rule.sources.contains(source) && !rule.requires.is_met_by(properties)) {
+                Some(rule) => excluded.push((*source, Exclusion::Rule(rule))),
+                None => included.push(*source),
+            }
+        }
+
+        // NOTE like upstream's CMake build, an arch-specific implementation replaces the generic
+        // one with the same name; otherwise both would end up in the archive and which one gets
+        // linked in would depend on the archive member order.
+        let arch_sources = included.iter()
+            .cloned()
+            .filter(|source| source.contains('/'))
+            .collect::<Vec<_>>();
+        included.retain(|source| {
+            if source.contains('/') {
+                return true;
+            }
+
+            match arch_sources.iter().find(|s| stem(s) == stem(source)) {
+                Some(arch_source) => {
+                    excluded.push((*source, Exclusion::ReplacedBy(arch_source)));
+                    false
+                }
+                None => true,
+            }
+        });
+
+        Selection {
+            included,
+            excluded,
+        }
+    }
+
+    /// Keeps only the sources that define the requested `symbols` and the sources those depend on
+    pub fn retain_needed(&mut self, symbols: &[String], properties: &Properties) {
+        let mut needed = vec![];
+        let mut pending = symbols.to_vec();
+        let mut resolved = vec![];
+
+        while let Some(symbol) = pending.pop() {
+            if resolved.contains(&symbol) {
+                continue;
+            }
+
+            let source = match self.included
+                .iter()
+                .find(|source| symbols::defined_by(source, properties).contains(&symbol)) {
+                Some(source) => *source,
+                None => panic!("{}", self.why_undefined(&symbol, properties)),
+            };
+
+            if !needed.contains(&source) {
+                needed.push(source);
+                pending.extend(symbols::dependencies_of(source).iter().map(|s| String::from(*s)));
+            }
+
+            resolved.push(symbol);
+        }
+
+        let excluded = &mut self.excluded;
+        self.included.retain(|source| {
+            if needed.contains(source) {
+                true
+            } else {
+                excluded.push((*source, Exclusion::NotNeeded));
+                false
+            }
+        });
+    }
+
+    /// Explains why no included source defines `symbol`
+    pub fn why_undefined(&self, symbol: &str, properties: &Properties) -> String {
+        match self.excluded
+            .iter()
+            .find(|&&(source, _)| symbols::defined_by(source, properties).iter().any(|s| s == symbol)) {
+            Some(&(source, ref exclusion)) => {
+                format!("`{}` is defined in {} which won't be compiled: {}",
+                        symbol,
+                        source,
+                        exclusion)
+            }
+            None => format!("no compiler-rt source defines `{}` for this target", symbol),
+        }
+    }
+}
+
+/// File name of `source` without its directory and extension, e.g. `arm/clzsi2.S` -> `clzsi2`
+fn stem(source: &str) -> &str {
+    Path::new(source).file_stem().and_then(|stem| stem.to_str()).unwrap_or(source)
+}
@@ -0,0 +1,37 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Macros (see `lib/builtins/assembly.h`) that start the definition of a routine
+const DEFINE_MACROS: &[&str] = &["DEFINE_COMPILERRT_FUNCTION(",
+                                 "DEFINE_COMPILERRT_PRIVATE_FUNCTION(",
+                                 "DEFINE_COMPILERRT_THUMB_FUNCTION("];
+
+/// Writes to `copy` the assembly `source` rewritten so that each of the routines it defines lives in
+/// its own `.text.$routine` section, like `-ffunction-sections` does for C code
+///
+/// Aliases (e.g. `DEFINE_AEABI_FUNCTION_ALIAS`) stay in the section of the routine they alias.
+// NOTE routines that branch to each other will still be next to each other after linking, because
+// the linker keeps the order in which input sections appear in the object file
+pub fn split(source: &Path, copy: &Path) {
+    let contents = &mut String::new();
+    try!(try!(File::open(source)).read_to_string(contents));
+
+    let mut rewritten = String::with_capacity(contents.len());
+    for line in contents.lines() {
+        let trimmed = line.trim_start();
+
+        if let Some(name) = DEFINE_MACROS.iter()
+            .find(|macro_| trimmed.starts_with(*macro_))
+            .and_then(|macro_| trimmed[macro_.len()..].split(')').next()) {
+            rewritten.push_str(&format!(".section .text.{},\"ax\",%progbits\n", name.trim()));
+            rewritten.push_str(".p2align 2\n");
+        }
+
+        rewritten.push_str(line);
+        rewritten.push('\n');
+    }
+
+    try!(fs::create_dir_all(copy.parent().unwrap()));
+    try!(try!(File::create(copy)).write_all(rewritten.as_bytes()));
+}
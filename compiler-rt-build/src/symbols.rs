@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use target::Properties;
+
+/// Symbols defined by sources that don't just define `__` + their own name, e.g. `clzsi2.c`
+/// defines `__clzsi2`
+// NOTE `__aeabi_*` symbols are only defined when compiling for ARM
+const SYMBOLS: &[(&str, &[&str])] =
+    &[("adddf3.c", &["__adddf3", "__aeabi_dadd"]),
+      ("addsf3.c", &["__addsf3", "__aeabi_fadd"]),
+      ("apple_versioning.c", &[]),
+      ("ashldi3.c", &["__ashldi3", "__aeabi_llsl"]),
+      ("ashrdi3.c", &["__ashrdi3", "__aeabi_lasr"]),
+      ("cmpdi2.c", &["__cmpdi2", "__aeabi_lcmp"]),
+      ("comparedf2.c",
+       &["__eqdf2", "__gedf2", "__gtdf2", "__ledf2", "__ltdf2", "__nedf2", "__unorddf2",
+         "__aeabi_dcmpun"]),
+      ("comparesf2.c",
+       &["__eqsf2", "__gesf2", "__gtsf2", "__lesf2", "__ltsf2", "__nesf2", "__unordsf2",
+         "__aeabi_fcmpun"]),
+      ("divdf3.c", &["__divdf3", "__aeabi_ddiv"]),
+      ("divsf3.c", &["__divsf3", "__aeabi_fdiv"]),
+      ("divsi3.c", &["__divsi3", "__aeabi_idiv"]),
+      ("extendhfsf2.c", &["__extendhfsf2", "__gnu_h2f_ieee", "__aeabi_h2f"]),
+      ("extendsfdf2.c", &["__extendsfdf2", "__aeabi_f2d"]),
+      ("fixdfdi.c", &["__fixdfdi", "__aeabi_d2lz"]),
+      ("fixdfsi.c", &["__fixdfsi", "__aeabi_d2iz"]),
+      ("fixsfdi.c", &["__fixsfdi", "__aeabi_f2lz"]),
+      ("fixsfsi.c", &["__fixsfsi", "__aeabi_f2iz"]),
+      ("fixunsdfdi.c", &["__fixunsdfdi", "__aeabi_d2ulz"]),
+      ("fixunsdfsi.c", &["__fixunsdfsi", "__aeabi_d2uiz"]),
+      ("fixunssfdi.c", &["__fixunssfdi", "__aeabi_f2ulz"]),
+      ("fixunssfsi.c", &["__fixunssfsi", "__aeabi_f2uiz"]),
+      ("floatdidf.c", &["__floatdidf", "__aeabi_l2d"]),
+      ("floatdisf.c", &["__floatdisf", "__aeabi_l2f"]),
+      ("floatsidf.c", &["__floatsidf", "__aeabi_i2d"]),
+      ("floatsisf.c", &["__floatsisf", "__aeabi_i2f"]),
+      ("floatundidf.c", &["__floatundidf", "__aeabi_ul2d"]),
+      ("floatundisf.c", &["__floatundisf", "__aeabi_ul2f"]),
+      ("floatunsidf.c", &["__floatunsidf", "__aeabi_ui2d"]),
+      ("floatunsisf.c", &["__floatunsisf", "__aeabi_ui2f"]),
+      ("int_util.c", &["__compilerrt_abort_impl"]),
+      ("lshrdi3.c", &["__lshrdi3", "__aeabi_llsr"]),
+      ("muldf3.c", &["__muldf3", "__aeabi_dmul"]),
+      ("muldi3.c", &["__muldi3", "__aeabi_lmul"]),
+      ("mulsf3.c", &["__mulsf3", "__aeabi_fmul"]),
+      ("negdf2.c", &["__negdf2", "__aeabi_dneg"]),
+      ("negsf2.c", &["__negsf2", "__aeabi_fneg"]),
+      ("subdf3.c", &["__subdf3", "__aeabi_dsub"]),
+      ("subsf3.c", &["__subsf3", "__aeabi_fsub"]),
+      // NOTE only defined on 32-bit PowerPC
+      ("trampoline_setup.c", &[]),
+      ("truncdfhf2.c", &["__truncdfhf2", "__aeabi_d2h"]),
+      ("truncdfsf2.c", &["__truncdfsf2", "__aeabi_d2f"]),
+      ("truncsfhf2.c", &["__truncsfhf2", "__gnu_f2h_ieee", "__aeabi_f2h"]),
+      ("ucmpdi2.c", &["__ucmpdi2", "__aeabi_ulcmp"]),
+      ("udivsi3.c", &["__udivsi3", "__aeabi_uidiv"]),
+      ("arm/aeabi_cdcmp.S", &["__aeabi_cdcmpeq", "__aeabi_cdcmple", "__aeabi_cdrcmple"]),
+      ("arm/aeabi_cfcmp.S", &["__aeabi_cfcmpeq", "__aeabi_cfcmple", "__aeabi_cfrcmple"]),
+      ("arm/aeabi_dcmp.S",
+       &["__aeabi_dcmpeq", "__aeabi_dcmpge", "__aeabi_dcmpgt", "__aeabi_dcmple",
+         "__aeabi_dcmplt"]),
+      ("arm/aeabi_div0.c", &["__aeabi_idiv0", "__aeabi_ldiv0"]),
+      ("arm/aeabi_fcmp.S",
+       &["__aeabi_fcmpeq", "__aeabi_fcmpge", "__aeabi_fcmpgt", "__aeabi_fcmple",
+         "__aeabi_fcmplt"]),
+      ("arm/aeabi_memcmp.S", &["__aeabi_memcmp", "__aeabi_memcmp4", "__aeabi_memcmp8"]),
+      ("arm/aeabi_memcpy.S", &["__aeabi_memcpy", "__aeabi_memcpy4", "__aeabi_memcpy8"]),
+      ("arm/aeabi_memmove.S", &["__aeabi_memmove", "__aeabi_memmove4", "__aeabi_memmove8"]),
+      ("arm/aeabi_memset.S",
+       &["__aeabi_memclr", "__aeabi_memclr4", "__aeabi_memclr8", "__aeabi_memset",
+         "__aeabi_memset4", "__aeabi_memset8"]),
+      ("arm/comparesf2.S",
+       &["__eqsf2", "__gesf2", "__gtsf2", "__lesf2", "__ltsf2", "__nesf2", "__unordsf2",
+         "__aeabi_fcmpun"]),
+      ("arm/divsi3.S", &["__divsi3", "__aeabi_idiv"]),
+      ("arm/udivsi3.S", &["__udivsi3", "__aeabi_uidiv"])];
+
+/// Symbols that a source calls but that are defined in *other* sources
+// NOTE calls into libc (e.g. `memcpy` in `arm/aeabi_memcpy.S`) and libm (e.g. `logb` in
+// `divdc3.c`) are not listed
+const DEPENDENCIES: &[(&str, &[&str])] =
+    &[("absvdi2.c", &["__compilerrt_abort_impl"]),
+      ("absvsi2.c", &["__compilerrt_abort_impl"]),
+      ("absvti2.c", &["__compilerrt_abort_impl"]),
+      ("addvdi3.c", &["__compilerrt_abort_impl"]),
+      ("addvsi3.c", &["__compilerrt_abort_impl"]),
+      ("addvti3.c", &["__compilerrt_abort_impl"]),
+      ("divdi3.c", &["__udivmoddi4"]),
+      ("divmoddi4.c", &["__divdi3"]),
+      ("divmodsi4.c", &["__divsi3"]),
+      ("divsi3.c", &["__udivsi3"]),
+      ("divti3.c", &["__udivmodti4"]),
+      ("moddi3.c", &["__udivmoddi4"]),
+      ("modsi3.c", &["__divsi3"]),
+      ("modti3.c", &["__udivmodti4"]),
+      ("mulvdi3.c", &["__compilerrt_abort_impl"]),
+      ("mulvsi3.c", &["__compilerrt_abort_impl"]),
+      ("mulvti3.c", &["__compilerrt_abort_impl"]),
+      ("negvdi2.c", &["__compilerrt_abort_impl"]),
+      ("negvsi2.c", &["__compilerrt_abort_impl"]),
+      ("negvti2.c", &["__compilerrt_abort_impl"]),
+      ("subdf3.c", &["__adddf3"]),
+      ("subsf3.c", &["__addsf3"]),
+      ("subvdi3.c", &["__compilerrt_abort_impl"]),
+      ("subvsi3.c", &["__compilerrt_abort_impl"]),
+      ("subvti3.c", &["__compilerrt_abort_impl"]),
+      ("udivdi3.c", &["__udivmoddi4"]),
+      ("udivmodsi4.c", &["__udivsi3"]),
+      ("udivti3.c", &["__udivmodti4"]),
+      ("umoddi3.c", &["__udivmoddi4"]),
+      ("umodsi3.c", &["__udivsi3"]),
+      ("umodti3.c", &["__udivmodti4"]),
+      ("arm/aeabi_cdcmp.S", &["__aeabi_cdcmpeq_check_nan", "__eqdf2", "__ltdf2"]),
+      ("arm/aeabi_cdcmpeq_check_nan.c", &["__aeabi_dcmpun"]),
+      ("arm/aeabi_cfcmp.S", &["__aeabi_cfcmpeq_check_nan", "__eqsf2", "__ltsf2"]),
+      ("arm/aeabi_cfcmpeq_check_nan.c", &["__aeabi_fcmpun"]),
+      ("arm/aeabi_dcmp.S", &["__eqdf2", "__gedf2", "__gtdf2", "__ledf2", "__ltdf2"]),
+      ("arm/aeabi_drsub.c", &["__aeabi_dsub"]),
+      ("arm/aeabi_fcmp.S", &["__eqsf2", "__gesf2", "__gtsf2", "__lesf2", "__ltsf2"]),
+      ("arm/aeabi_frsub.c", &["__aeabi_fsub"]),
+      ("arm/aeabi_idivmod.S", &["__divmodsi4", "__divsi3"]),
+      ("arm/aeabi_ldivmod.S", &["__divmoddi4"]),
+      ("arm/aeabi_uidivmod.S", &["__udivmodsi4", "__udivsi3"]),
+      ("arm/aeabi_uldivmod.S", &["__udivmoddi4"]),
+      ("arm/divmodsi4.S", &["__udivmodsi4"]),
+      ("arm/divsi3.S", &["__udivsi3"]),
+      ("arm/modsi3.S", &["__umodsi3"])];
+
+/// Symbols defined by `source` when compiled for a target with these `properties`
+pub fn defined_by(source: &str, properties: &Properties) -> Vec<String> {
+    match SYMBOLS.iter().find(|&&(s, _)| s == source) {
+        Some(&(_, symbols)) => {
+            symbols.iter()
+                .filter(|symbol| properties.arch == "arm" || !symbol.starts_with("__aeabi_"))
+                .map(|symbol| String::from(*symbol))
+                .collect()
+        }
+        None => {
+            let stem = Path::new(source).file_stem().and_then(|stem| stem.to_str()).unwrap();
+
+            vec![format!("__{}", stem)]
+        }
+    }
+}
+
+/// Symbols `source` needs from other sources
+pub fn dependencies_of(source: &str) -> &'static [&'static str] {
+    DEPENDENCIES.iter().find(|&&(s, _)| s == source).map(|&(_, symbols)| symbols).unwrap_or(&[])
+}
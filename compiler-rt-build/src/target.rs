@@ -0,0 +1,297 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+/// Parsed target specification file
+pub struct Spec(BTreeMap<String, Value>);
+
+impl Spec {
+    fn arch(&self) -> &str {
+        self.mandatory("arch")
+    }
+
+    fn linker(&self) -> Option<&str> {
+        self.optional("linker")
+    }
+
+    fn llvm_target(&self) -> &str {
+        self.mandatory("llvm-target")
+    }
+
+    fn mandatory(&self, field: &str) -> &str {
+        match self.0[field] {
+            Value::String(ref s) => s,
+            _ => unreachable!(),
+        }
+    }
+
+    fn optional(&self, field: &str) -> Option<&str> {
+        self.0.get(field).map(|field| {
+            match *field {
+                Value::String(ref s) => &**s,
+                _ => unreachable!(),
+            }
+        })
+    }
+
+    fn os(&self) -> &str {
+        self.mandatory("os")
+    }
+
+    fn pointer_width(&self) -> &str {
+        self.mandatory("target-pointer-width")
+    }
+}
+
+/// Parses the target specification file at `path`
+fn parse(path: &Path) -> Spec {
+    let json = &mut String::new();
+    try!(try!(File::open(path)).read_to_string(json));
+
+    try!(serde_json::from_str(json).map(Spec))
+}
+
+/// A compilation target, built-in or custom
+pub struct Target {
+    pub name: String,
+    spec: Option<Spec>,
+}
+
+impl Target {
+    /// The built-in target or the custom target whose specification file, `$target.json`, is in
+    /// the current directory or in `$RUST_TARGET_PATH`, like `rustc` does
+    // TODO somehow read the specification of built-in targets. This probably requires upstream
+    // (`rustc`) support.
+    pub fn new(target: &str) -> Self {
+        /// Parse `target` specification file in `dir`ectory, if it's there
+        fn parse_in(target: &str, dir: &Path) -> Option<Spec> {
+            let path = dir.join(format!("{}.json", target));
+
+            if path.exists() { Some(parse(&path)) } else { None }
+        }
+
+        Target {
+            name: target.to_owned(),
+            spec: parse_in(target, &try!(env::current_dir())).or_else(|| {
+                env::var_os("RUST_TARGET_PATH")
+                    .map(PathBuf::from)
+                    .and_then(|dir| parse_in(target, &dir))
+            }),
+        }
+    }
+
+    /// The custom target specified by the file at `path`. The name of the target is the name of the
+    /// file without its `.json` extension
+    pub fn from_spec(path: &Path) -> Self {
+        Target {
+            name: path.file_stem().unwrap().to_string_lossy().into_owned(),
+            spec: Some(parse(path)),
+        }
+    }
+
+    /// Architecture, using the names of the `arch` field of target specifications
+    pub fn arch(&self) -> &str {
+        self.spec.as_ref().map(|spec| spec.arch()).unwrap_or_else(|| {
+            let arch = self.llvm_target().split('-').next().unwrap_or("");
+
+            if arch.starts_with("arm") || arch.starts_with("thumb") {
+                "arm"
+            } else if arch.starts_with('i') && arch.ends_with("86") {
+                "x86"
+            } else if arch.starts_with("mips64") {
+                "mips64"
+            } else if arch.starts_with("mips") {
+                "mips"
+            } else if arch.starts_with("powerpc64") {
+                "powerpc64"
+            } else {
+                arch
+            }
+        })
+    }
+
+    pub fn cpu(&self) -> Option<&str> {
+        self.spec.as_ref().and_then(|spec| spec.optional("cpu"))
+    }
+
+    pub fn features(&self) -> Option<&str> {
+        self.spec.as_ref().and_then(|spec| spec.optional("features"))
+    }
+
+    fn has_feature(&self, feature: &str) -> bool {
+        self.features().map(|f| f.contains(feature)) == Some(true)
+    }
+
+    pub fn llvm_target(&self) -> &str {
+        // TODO(unwrap_or) for *most* built-in targets, their name matches its `llvm-target` field.
+        // The exceptions (e.g. aarch64-apple-ios) should be handled here.
+        self.spec.as_ref().map(|spec| spec.llvm_target()).unwrap_or(&self.name)
+    }
+
+    /// Operating system, using the names of the `os` field of target specifications
+    pub fn os(&self) -> &str {
+        self.spec.as_ref().map(|spec| spec.os()).unwrap_or_else(|| {
+            let mut parts = self.llvm_target().split('-').skip(1);
+
+            match (parts.next(), parts.next()) {
+                (Some("none"), _) => "none",
+                (_, Some(os)) => os,
+                _ => "unknown",
+            }
+        })
+    }
+
+    fn pointer_width(&self) -> u32 {
+        self.spec
+            .as_ref()
+            .map(|spec| try!(spec.pointer_width().parse()))
+            .unwrap_or_else(|| if self.arch().contains("64") { 64 } else { 32 })
+    }
+
+    /// Resolves the properties that decide which sources get compiled and with which flags
+    pub fn properties(&self) -> Properties<'_> {
+        let llvm_target = self.llvm_target();
+        let profile = if llvm_target.starts_with("thumbv6m") {
+            Profile::V6m
+        } else if llvm_target.starts_with("thumbv7em") {
+            Profile::V7em
+        } else if llvm_target.starts_with("thumbv7m") {
+            Profile::V7m
+        } else {
+            Profile::Other
+        };
+
+        let fpu = match self.cpu() {
+            Some("cortex-m4") if !self.has_feature("+soft-float") => Fpu::Single,
+            Some("cortex-m7") if self.has_feature("+fp-only-sp") => Fpu::Single,
+            Some("cortex-m7") if !self.has_feature("+soft-float") => Fpu::Double,
+            _ => Fpu::None,
+        };
+
+        let arch = self.arch();
+        let long_double = match arch {
+            "x86" | "x86_64" => LongDouble::X87,
+            "aarch64" | "mips64" | "s390x" | "sparc64" => LongDouble::Binary128,
+            "powerpc" | "powerpc64" => LongDouble::DoubleDouble,
+            _ => LongDouble::Binary64,
+        };
+
+        let float_abi = if llvm_target.ends_with("eabihf") {
+            FloatAbi::Hard
+        } else if fpu != Fpu::None {
+            FloatAbi::SoftFp
+        } else {
+            FloatAbi::Soft
+        };
+
+        Properties {
+            arch,
+            profile,
+            thumb: llvm_target.starts_with("thumb"),
+            fpu,
+            float_abi,
+            os: self.os(),
+            int128: self.pointer_width() == 64,
+            long_double,
+        }
+    }
+
+    pub fn tool(&self, env: &str, tool: &str) -> Cow<'_, str> {
+        self.try_tool(env, tool)
+            .unwrap_or_else(|| panic!("{}_{} not set", env, self.name.replace("-", "_")))
+    }
+
+    /// Like `tool` but returns `None` if the tool can't be derived from the environment or from
+    /// the `linker` field
+    pub fn try_tool<'a>(&'a self, env: &str, tool: &str) -> Option<Cow<'a, str>> {
+        let tool_env = &format!("{}_{}", env, self.name.replace("-", "_"));
+
+        env::var(tool_env)
+            .ok()
+            .or_else(|| {
+                self.spec.as_ref().and_then(|spec| spec.linker()).and_then(|linker| {
+                    if linker.ends_with("gcc") {
+                        Some(linker.replace("gcc", tool))
+                    } else {
+                        None
+                    }
+                })
+            })
+            .map(Cow::from)
+    }
+}
+
+/// Properties of a target that the source selection rules and the compiler flags depend on
+#[derive(Debug)]
+pub struct Properties<'a> {
+    pub arch: &'a str,
+    /// ISA profile
+    pub profile: Profile,
+    /// Whether code is generated in Thumb state (as opposed to ARM state)
+    pub thumb: bool,
+    pub fpu: Fpu,
+    pub float_abi: FloatAbi,
+    pub os: &'a str,
+    /// Whether the C compiler supports 128-bit integers (`CRT_HAS_128BIT`)
+    pub int128: bool,
+    /// Format of the C `long double` type
+    pub long_double: LongDouble,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Profile {
+    /// ARMv6-M (Cortex-M0, Cortex-M0+, Cortex-M1)
+    V6m,
+    /// ARMv7-M (Cortex-M3)
+    V7m,
+    /// ARMv7E-M (Cortex-M4, Cortex-M7)
+    V7em,
+    Other,
+}
+
+/// Floating point unit, by the precision it supports
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Fpu {
+    None,
+    Single,
+    Double,
+}
+
+/// How floating point values are passed to and returned from functions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FloatAbi {
+    /// In integer registers; floating point operations are done in software
+    Soft,
+    /// In integer registers, but floating point operations may use the FPU
+    SoftFp,
+    /// In FPU registers
+    Hard,
+}
+
+impl FloatAbi {
+    /// Value of GCC's `-mfloat-abi` flag
+    pub fn name(&self) -> &'static str {
+        match *self {
+            FloatAbi::Soft => "soft",
+            FloatAbi::SoftFp => "softfp",
+            FloatAbi::Hard => "hard",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LongDouble {
+    /// Same as `double`
+    Binary64,
+    /// x87 80-bit extended precision
+    X87,
+    /// IEEE quadruple precision (`CRT_LDBL_128BIT`)
+    Binary128,
+    /// PowerPC's pair of `double`s
+    DoubleDouble,
+}
@@ -0,0 +1,156 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use gcc::Tool;
+
+use Build;
+use target::{Fpu, Profile, Properties, Target};
+
+/// The archiver the library is assembled with
+pub fn archiver(build: &Build) -> String {
+    if let Some(ref archiver) = build.archiver {
+        archiver.display().to_string()
+    } else if build.target.name != build.get_host() {
+        build.target.tool("AR", "ar").into_owned()
+    } else {
+        env::var("AR").unwrap_or_else(|_| String::from("ar"))
+    }
+}
+
+/// Configures the toolchain and the flags every source of the `src` checkout is compiled with
+// NOTE `gcc` has been renamed to `cc`, which adds other default flags
+#[allow(deprecated)]
+pub fn configure(build: &Build,
+                 properties: &Properties,
+                 archiver: &str,
+                 src: &Path)
+                 -> gcc::Build {
+    let target = &build.target;
+    let mut config = gcc::Build::new();
+    config.target(&target.name).host(&build.get_host()).out_dir(build.get_out_dir());
+
+    if let Some(ref compiler) = build.compiler {
+        config.archiver(Path::new(archiver));
+        config.compiler(compiler);
+    } else if target.name != build.get_host() {
+        config.archiver(Path::new(archiver));
+        config.compiler(Path::new(&*target.tool("CC", "gcc")));
+    }
+
+    // NOTE the `gcc` crate takes care of passing the user flags in `CFLAGS_$TARGET`,
+    // `TARGET_CFLAGS` (or `HOST_CFLAGS`) and `CFLAGS` to the compiler
+
+    // Optimization level, e.g. Cargo's `opt-level`. `s` maps to `-Os` and `z` maps to `-Oz` (`-Os`
+    // if the compiler is GCC, which doesn't support `-Oz`)
+    config.opt_level_str(&build.get_opt_level());
+    config.debug(build.get_debug());
+
+    // Like CMake's Release configuration
+    if build.get_release() {
+        config.define("NDEBUG", None);
+    }
+
+    // ARM arch optimization
+    match properties.profile {
+        Profile::V6m => {
+            config.flag("-march=armv6-m");
+        }
+        Profile::V7m => {
+            config.flag("-march=armv7-m");
+        }
+        Profile::V7em => {
+            config.flag("-march=armv7e-m");
+        }
+        Profile::Other => {}
+    }
+
+    // CPU optimization
+    if let Some(cpu) = target.cpu() {
+        config.flag(&format!("-mcpu={}", cpu));
+    }
+
+    // THUMB mode
+    if properties.thumb {
+        config.flag("-mthumb");
+    }
+
+    // FPU
+    if let Some(fpu) = fpu(target, properties) {
+        config.flag(&format!("-mfpu={}", fpu));
+    }
+
+    // Float ABI. Set explicitly rather than relying on the compiler's default, which may not match
+    // what `rustc` uses for the target
+    if properties.arch == "arm" {
+        config.flag(&format!("-mfloat-abi={}", properties.float_abi.name()));
+    }
+
+    // Keep the location of the (temporary) source directory out of the debug info and out of the
+    // `__FILE__` strings
+    config.flag(&format!("-fdebug-prefix-map={}=/compiler-rt", src.display()));
+
+    config
+}
+
+/// Flags that are only passed to the compiler if it supports them, e.g. GCC older than 8 doesn't
+/// know `-fmacro-prefix-map`
+///
+/// NOTE planning doesn't invoke the compiler; these are probed when the plan is compiled
+pub fn optional_flags(src: &Path) -> Vec<String> {
+    vec![format!("-fmacro-prefix-map={}=/compiler-rt", src.display())]
+}
+
+/// Whether `compiler` accepts `flag`
+// NOTE `Config::flag_if_supported` probes the compiler the `gcc` crate would pick for the target,
+// which isn't necessarily the one configured here
+pub fn supports(compiler: &Tool, flag: &str, out_dir: &Path) -> bool {
+    try!(fs::create_dir_all(out_dir));
+    let check = out_dir.join("flag_check.c");
+    try!(try!(File::create(&check)).write_all(b"int flag_check;\n"));
+
+    compiler.to_command()
+        .arg(flag)
+        .arg("-c")
+        .arg(&check)
+        .arg("-o")
+        .arg(out_dir.join("flag_check.o"))
+        .output()
+        .map(|output| output.status.success() && output.stderr.is_empty())
+        .unwrap_or(false)
+}
+
+/// Value of the `-mfpu` flag
+pub fn fpu(target: &Target, properties: &Properties) -> Option<&'static str> {
+    match (target.cpu(), properties.fpu) {
+        (Some("cortex-m4"), Fpu::Single) => Some("fpv4-sp-d16"),
+        (Some("cortex-m7"), Fpu::Single) => Some("fpv5-sp-d16"),
+        (Some("cortex-m7"), Fpu::Double) => Some("fpv5-d16"),
+        _ => None,
+    }
+}
+
+/// Command that compiles `input`, the compiler-rt `source` (relative to `lib/builtins`) in the `src`
+/// checkout or a rewritten copy of it, into `object`
+pub fn command(compiler: &Tool, src: &Path, source: &str, input: &Path, object: &Path) -> Command {
+    let path = src.join("lib/builtins").join(source);
+
+    let mut cmd = compiler.to_command();
+    cmd.current_dir(src);
+    if input != path {
+        // NOTE the sources include the headers relative to their own directory, e.g.
+        // `#include "../assembly.h"`
+        cmd.arg("-I").arg(path.parent().unwrap());
+
+        // Like the checkout, keep the location of the copy out of the debug info
+        let copies = input.ancestors().nth(Path::new(source).components().count()).unwrap();
+        cmd.arg(format!("-fdebug-prefix-map={}=/compiler-rt/lib/builtins", copies.display()));
+    }
+    cmd.arg("-c")
+        .arg(input)
+        .arg("-o")
+        .arg(object);
+    cmd
+}
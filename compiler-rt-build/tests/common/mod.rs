@@ -0,0 +1,225 @@
+//! Code shared by the test suites
+
+#![allow(dead_code)]
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use compiler_rt_build::{Build, Plan};
+
+macro_rules! try {
+    ($e:expr) => {
+        $e.unwrap_or_else(|e| panic!("{} with {}", stringify!($e), e))
+    }
+}
+
+/// Fake compiler / archiver: records its invocation and creates the file the real tool would have
+/// created
+///
+/// The compiler copies the object `fake_objects` prepared for the source, if any. The archiver
+/// writes a GNU archive, with every member name in the long name table and without symbol index,
+/// or, if there's a `bsd` file next to it, a BSD archive like the one of macOS.
+const FAKE_TOOL: &str = r#"#!/bin/sh
+echo "$0 $*" >> "$(dirname "$0")/invocations"
+
+case "$(basename "$0")" in
+    *-ar)
+        archive=$2
+        shift 2
+
+        if [ -f "$(dirname "$0")/bsd" ]; then
+            {
+                printf '!<arch>\n'
+                printf '#1/20           %-12s%-6s%-6s%-8s%-10s`\n' 0 0 0 644 28
+                printf '__.SYMDEF SORTED\0\0\0\0\0\0\0\0\0\0\0\0'
+
+                for object; do
+                    # NOTE the name is padded with NULs to a multiple of 8 bytes
+                    name=$(basename "$object")
+                    padded=$(((${#name} + 7) / 8 * 8))
+                    size=$(($(wc -c < "$object") + padded))
+                    printf '#1/%-13s%-12s%-6s%-6s%-8s%-10s`\n' $padded 0 0 0 644 $size
+                    printf '%s' "$name"
+                    head -c $((padded - ${#name})) /dev/zero
+                    cat "$object"
+                    [ $((size % 2)) = 0 ] || printf '\n'
+                done
+            } > "$archive"
+            exit
+        fi
+
+        names=
+        for object; do
+            names="$names$(basename "$object")/
+"
+        done
+
+        {
+            printf '!<arch>\n'
+            printf '%-48s%-10s`\n' // ${#names}
+            printf '%s' "$names"
+            [ $((${#names} % 2)) = 0 ] || printf '\n'
+
+            offset=0
+            for object; do
+                name=$(basename "$object")
+                size=$(($(wc -c < "$object")))
+                printf '/%-15s%-12s%-6s%-6s%-8s%-10s`\n' $offset 0 0 0 644 $size
+                cat "$object"
+                [ $((size % 2)) = 0 ] || printf '\n'
+                offset=$((offset + ${#name} + 2))
+            done
+        } > "$archive"
+        ;;
+    *)
+        while [ $# -gt 0 ]; do
+            case "$1" in
+                # NOTE the assembly sources may be compiled from a copy in `$OUT_DIR/sections`
+                -c) source=${2#*/lib/builtins/}; source=${source#*/sections/} ;;
+                -o) object=$2 ;;
+            esac
+            shift
+        done
+
+        if [ -f "$(dirname "$0")/objects/$source.o" ]; then
+            cp "$(dirname "$0")/objects/$source.o" "$object"
+        else
+            touch "$object"
+        fi
+        ;;
+esac
+"#;
+
+/// Installs fake `arm-none-eabi-gcc` and `arm-none-eabi-ar` tools in `td/bin`; returns the
+/// directory, where the tools record their invocations (`invocations`)
+pub fn fake_toolchain(td: &Path) -> PathBuf {
+    let bin = td.join("bin");
+    try!(fs::create_dir(&bin));
+    for tool in &["arm-none-eabi-gcc", "arm-none-eabi-ar"] {
+        let path = bin.join(tool);
+        write(&path, FAKE_TOOL);
+        try!(fs::set_permissions(&path, fs::Permissions::from_mode(0o755)));
+    }
+
+    bin
+}
+
+/// Makes `build` use the fake tools in `bin`
+///
+/// NOTE the variables that would add flags to the compiler are checked rather than cleared: the
+/// tests of a suite run in parallel and changing the environment would race with them
+pub fn fake_tools(build: &mut Build, bin: &Path) {
+    let target = build.target().name.clone();
+    for var in &[String::from("CFLAGS"),
+                 String::from("TARGET_CFLAGS"),
+                 String::from("CRATE_CC_NO_DEFAULTS"),
+                 format!("CFLAGS_{}", target),
+                 format!("CFLAGS_{}", target.replace("-", "_"))] {
+        assert!(env::var_os(var).is_none(), "unset {} to run the tests", var);
+    }
+
+    build.compiler(bin.join("arm-none-eabi-gcc")).archiver(bin.join("arm-none-eabi-ar"));
+}
+
+/// Makes the fake compiler in `bin` "compile" each source the `plan` includes into an ELF object
+/// that defines the symbols the real object would define
+pub fn fake_objects(bin: &Path, plan: &Plan) {
+    fake_objects_with(bin, plan, elf_object)
+}
+
+/// Like `fake_objects`, but the objects are what `object` makes of the symbols they define
+pub fn fake_objects_with(bin: &Path, plan: &Plan, object: fn(&[String]) -> Vec<u8>) {
+    for source in plan.included() {
+        let path = bin.join("objects").join(format!("{}.o", source));
+        try!(fs::create_dir_all(path.parent().unwrap()));
+        try!(try!(File::create(path)).write_all(&object(&plan.defined_by(source))));
+    }
+}
+
+/// An object that isn't ELF: only the header of an (empty) 64-bit Mach-O object
+pub fn macho_object(_symbols: &[String]) -> Vec<u8> {
+    let mut bytes = b"\xcf\xfa\xed\xfe".to_vec();
+    bytes.resize(32, 0);
+    bytes
+}
+
+/// A relocatable ELF object for 32-bit, little endian ARM whose `.text` section defines the
+/// global functions `symbols`
+pub fn elf_object(symbols: &[String]) -> Vec<u8> {
+    /// Size of the ELF header
+    const EHSIZE: usize = 52;
+    /// Size of a section header
+    const SHENTSIZE: usize = 40;
+    /// Size of a symbol table entry
+    const SYMENT: usize = 16;
+
+    let shstrtab = b"\0.text\0.symtab\0.strtab\0.shstrtab\0";
+    let mut strtab = vec![0];
+    let mut symtab = vec![0; SYMENT];
+    for symbol in symbols {
+        let name = strtab.len() as u32;
+        strtab.extend_from_slice(symbol.as_bytes());
+        strtab.push(0);
+
+        symtab.extend_from_slice(&name.to_le_bytes());
+        // value and size
+        symtab.extend_from_slice(&[0; 8]);
+        // `STB_GLOBAL`, `STT_FUNC`
+        symtab.extend_from_slice(&[1 << 4 | 2, 0]);
+        // `.text`
+        symtab.extend_from_slice(&1u16.to_le_bytes());
+    }
+
+    // NOTE the symbol table and the section headers are 4-byte aligned
+    let pad = |n: usize| (n + 3) & !3;
+    let symtab_offset = EHSIZE;
+    let strtab_offset = symtab_offset + symtab.len();
+    let shstrtab_offset = strtab_offset + strtab.len();
+    let shoff = pad(shstrtab_offset + shstrtab.len());
+
+    let mut bytes = vec![];
+    bytes.extend_from_slice(b"\x7fELF\x01\x01\x01");
+    bytes.resize(16, 0);
+    // `ET_REL`, `EM_ARM`, `EV_CURRENT`, no entry point nor program headers, the section headers,
+    // EABI version 5 and the sizes and number of the headers; `.shstrtab` is the last section
+    let header = [(1, 2), (40, 2), (1, 4), (0, 4), (0, 4), (shoff, 4), (0x0500_0000, 4),
+                  (EHSIZE, 2), (0, 2), (0, 2), (SHENTSIZE, 2), (5, 2), (4, 2)];
+    for &(field, size) in &header {
+        bytes.extend_from_slice(&(field as u32).to_le_bytes()[..size]);
+    }
+    assert_eq!(bytes.len(), EHSIZE);
+
+    bytes.extend_from_slice(&symtab);
+    bytes.extend_from_slice(&strtab);
+    bytes.extend_from_slice(shstrtab);
+    bytes.resize(shoff, 0);
+
+    // name, type, flags, address, offset, size, link, info, alignment and entry size of the null
+    // section, `.text`, `.symtab`, `.strtab` and `.shstrtab`
+    let sections = [[0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                    [1, 1, 6, 0, EHSIZE, 0, 0, 0, 4, 0],
+                    [7, 2, 0, 0, symtab_offset, symtab.len(), 3, 1, 4, SYMENT],
+                    [15, 3, 0, 0, strtab_offset, strtab.len(), 0, 0, 1, 0],
+                    [23, 3, 0, 0, shstrtab_offset, shstrtab.len(), 0, 0, 1, 0]];
+    for section in &sections {
+        for &field in section {
+            bytes.extend_from_slice(&(field as u32).to_le_bytes());
+        }
+    }
+
+    bytes
+}
+
+pub fn read(path: &Path) -> String {
+    let contents = &mut String::new();
+    try!(try!(File::open(path)).read_to_string(contents));
+    contents.clone()
+}
+
+pub fn write(path: &Path, contents: &str) {
+    try!(fs::create_dir_all(path.parent().unwrap()));
+    try!(try!(File::create(path)).write_all(contents.as_bytes()));
+}
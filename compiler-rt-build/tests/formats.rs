@@ -0,0 +1,87 @@
+//! Tests of the archives and objects of other hosts and targets than GNU `ar` and ELF, e.g. the BSD
+//! archives of macOS and the Mach-O objects of Apple targets
+//!
+//! The tools are the fake ones of the snapshot tests; no sources are needed.
+
+#![cfg(unix)]
+
+extern crate compiler_rt_build;
+extern crate tempdir;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use compiler_rt_build::{Build, Target};
+use tempdir::TempDir;
+
+#[macro_use]
+mod common;
+
+#[test]
+fn bsd_archives_are_read() {
+    let td = try!(TempDir::new("formats"));
+    let fixture = build(td.path());
+    common::fake_objects(&fixture.bin, &fixture.build.plan());
+    common::write(&fixture.bin.join("bsd"), "");
+
+    let output = fixture.build.compile();
+    assert_eq!(output.symbols, fixture.symbols);
+
+    let manifest = common::read(&fixture.out.join("symbols.json"));
+    for symbol in &fixture.symbols {
+        assert!(manifest.contains(&format!("\"name\": \"{}\"", symbol)));
+    }
+}
+
+#[test]
+fn the_manifest_and_checks_are_skipped_for_non_elf_objects() {
+    let td = try!(TempDir::new("formats"));
+    let fixture = build(td.path());
+    common::fake_objects_with(&fixture.bin, &fixture.build.plan(), common::macho_object);
+
+    // Left by a previous build
+    let manifest = fixture.out.join("symbols.json");
+    common::write(&manifest, "[]");
+
+    // NOTE the archive doesn't define any symbol, yet the build doesn't fail
+    let output = fixture.build.compile();
+    assert_eq!(output.symbols, fixture.symbols);
+    assert_eq!(output.missing, Vec::<String>::new());
+    assert!(!manifest.exists());
+}
+
+struct Fixture {
+    build: Build,
+    bin: PathBuf,
+    out: PathBuf,
+    /// The symbols the included sources define, sorted
+    symbols: Vec<String>,
+}
+
+/// A build, in `td`, of all the intrinsics of a target, failing if any is missing
+fn build(td: &Path) -> Fixture {
+    let bin = common::fake_toolchain(td);
+    let src = td.join("compiler-rt");
+    let out = td.join("out");
+    try!(fs::create_dir_all(&src));
+
+    let mut build = Build::new(Target::from_spec(Path::new("../cortex-m3.json")));
+    build.src(&src)
+        .out_dir(&out)
+        .host("x86_64-unknown-linux-gnu")
+        .opt_level("s")
+        .debug(false)
+        .release(false);
+    common::fake_tools(&mut build, &bin);
+
+    let mut symbols = build.plan().symbols();
+    symbols.sort();
+    symbols.dedup();
+
+    Fixture {
+        build,
+        bin,
+        out,
+        symbols,
+    }
+}
@@ -0,0 +1,89 @@
+//! Tests of the reuse of the objects of a previous build
+//!
+//! The sources are built with the fake tools of the snapshot tests, from a checkout that contains
+//! placeholder sources and headers.
+
+#![cfg(unix)]
+
+extern crate compiler_rt_build;
+extern crate tempdir;
+
+use std::fs;
+use std::path::Path;
+
+use compiler_rt_build::{Build, MissingSymbols, Target};
+use tempdir::TempDir;
+
+#[macro_use]
+mod common;
+
+#[test]
+fn only_stale_objects_are_recompiled() {
+    let td = try!(TempDir::new("incremental"));
+    let bin = common::fake_toolchain(td.path());
+    let src = td.path().join("compiler-rt");
+    let out = td.path().join("out");
+
+    let build = |opt_level: &str| {
+        let mut build = Build::new(Target::from_spec(Path::new("../cortex-m3.json")));
+        build.src(&src)
+            .out_dir(&out)
+            .host("x86_64-unknown-linux-gnu")
+            .opt_level(opt_level)
+            .debug(false)
+            .release(false)
+            .symbols(vec!["__popcountsi2".to_owned(), "__divdi3".to_owned()])
+            .missing_symbols(MissingSymbols::Warn);
+        common::fake_tools(&mut build, &bin);
+        build
+    };
+
+    let included = build("s").plan().included().to_owned();
+    common::fake_objects(&bin, &build("s").plan());
+    assert!(included.len() > 1);
+    for source in &included {
+        common::write(&src.join("lib/builtins").join(source), source);
+    }
+    common::write(&src.join("lib/builtins/int_lib.h"), "#define INT_LIB_H");
+
+    let compile = |opt_level: &str| {
+        let invocations = bin.join("invocations");
+        if invocations.exists() {
+            try!(fs::remove_file(&invocations));
+        }
+
+        let output = build(opt_level).compile();
+        assert_eq!(output.included, included);
+
+        let mut compiled = output.compiled;
+        compiled.sort();
+        compiled
+    };
+    let all = || {
+        let mut all = included.clone();
+        all.sort();
+        all
+    };
+
+    assert_eq!(compile("s"), all());
+    assert_eq!(compile("s"), Vec::<&str>::new());
+
+    // A patched source
+    let patched = included[0];
+    common::write(&src.join("lib/builtins").join(patched), "patched");
+    assert_eq!(compile("s"), vec![patched]);
+    assert_eq!(compile("s"), Vec::<&str>::new());
+
+    // A deleted object
+    let deleted = included[1];
+    try!(fs::remove_file(out.join(deleted).with_extension("o")));
+    assert_eq!(compile("s"), vec![deleted]);
+
+    // Different flags
+    assert_eq!(compile("0"), all());
+    assert_eq!(compile("0"), Vec::<&str>::new());
+
+    // A patched header
+    common::write(&src.join("lib/builtins/int_lib.h"), "#define INT_LIB_H 1");
+    assert_eq!(compile("0"), all());
+}
@@ -0,0 +1,182 @@
+//! Tests of the application of local patches to the compiler-rt sources
+//!
+//! Like the incremental tests, the sources are placeholders and the tools are the fake ones of the
+//! snapshot tests.
+
+#![cfg(unix)]
+
+extern crate compiler_rt_build;
+extern crate tempdir;
+
+use std::path::{Path, PathBuf};
+
+use compiler_rt_build::{Build, MissingSymbols, Target};
+use tempdir::TempDir;
+
+#[macro_use]
+mod common;
+
+const FIX: &str = "\
+--- a/lib/builtins/popcountsi2.c
++++ b/lib/builtins/popcountsi2.c
+@@ -1 +1,2 @@
+ popcountsi2.c
++fix
+";
+
+/// Only applies on top of `FIX`
+const FIX_OF_THE_FIX: &str = "\
+--- a/lib/builtins/popcountsi2.c
++++ b/lib/builtins/popcountsi2.c
+@@ -1,2 +1,2 @@
+ popcountsi2.c
+-fix
++fix of the fix
+";
+
+/// Conflicts with `FIX`
+const ANOTHER_FIX: &str = "\
+--- a/lib/builtins/popcountsi2.c
++++ b/lib/builtins/popcountsi2.c
+@@ -1 +1,2 @@
+ popcountsi2.c
++another fix
+";
+
+#[test]
+fn patches_are_applied_in_order() {
+    let td = try!(TempDir::new("patches"));
+    common::fake_toolchain(td.path());
+    let patches = td.path().join("patches");
+    // NOTE `FIX` must be applied first
+    common::write(&patches.join("0001-fix.patch"), FIX);
+    common::write(&patches.join("0002-fix-of-the-fix.diff"), FIX_OF_THE_FIX);
+    common::write(&patches.join("README"), "not a patch");
+
+    let build = build(td.path(), &patches);
+    let popcountsi2 = build.src.join("lib/builtins/popcountsi2.c");
+    let patched = build.out.join("patched/lib/builtins/popcountsi2.c");
+    let applied = vec![patches.join("0001-fix.patch"), patches.join("0002-fix-of-the-fix.diff")];
+
+    assert_eq!(build.build.plan().patches(), &*applied);
+
+    let output = build.build.compile();
+    assert_eq!(output.patches, applied);
+    assert_eq!(common::read(&patched), "popcountsi2.c\nfix of the fix\n");
+    assert_eq!(common::read(&build.out.join("patches.txt")),
+               "0001-fix.patch\n0002-fix-of-the-fix.diff");
+
+    // The patches are applied to a copy; the checkout isn't modified
+    assert_eq!(common::read(&popcountsi2), "popcountsi2.c\n");
+
+    // The copy is patched again but the sources are the same, so nothing is recompiled
+    let output = build.build.compile();
+    assert_eq!(output.patches, applied);
+    assert_eq!(output.compiled, Vec::<&str>::new());
+    assert_eq!(common::read(&patched), "popcountsi2.c\nfix of the fix\n");
+}
+
+#[test]
+fn another_series_starts_from_the_checkout() {
+    let td = try!(TempDir::new("patches"));
+    common::fake_toolchain(td.path());
+    let (fix, another_fix) = (td.path().join("fix"), td.path().join("another-fix"));
+    common::write(&fix.join("0001-fix.patch"), FIX);
+    // NOTE conflicts with `FIX`, so it only applies to the unpatched sources
+    common::write(&another_fix.join("0001-another-fix.patch"), ANOTHER_FIX);
+
+    let mut build = build(td.path(), &fix);
+    let patched = build.out.join("patched/lib/builtins/popcountsi2.c");
+    build.build.compile();
+    assert_eq!(common::read(&patched), "popcountsi2.c\nfix\n");
+
+    build.build.patches(&another_fix);
+    let output = build.build.compile();
+    assert_eq!(output.patches, vec![another_fix.join("0001-another-fix.patch")]);
+    assert_eq!(output.compiled, vec!["popcountsi2.c"]);
+    assert_eq!(common::read(&patched), "popcountsi2.c\nanother fix\n");
+    assert_eq!(common::read(&build.src.join("lib/builtins/popcountsi2.c")), "popcountsi2.c\n");
+}
+
+#[test]
+#[should_panic(expected = "0002-fix-of-the-fix.patch doesn't apply to the compiler-rt sources")]
+fn a_patch_that_does_not_apply_fails_the_build() {
+    let td = try!(TempDir::new("patches"));
+    common::fake_toolchain(td.path());
+    let patches = td.path().join("patches");
+    // NOTE `FIX` is missing
+    common::write(&patches.join("0002-fix-of-the-fix.patch"), FIX_OF_THE_FIX);
+
+    build(td.path(), &patches).build.compile();
+}
+
+#[test]
+fn a_partially_applied_series_is_completed() {
+    let td = try!(TempDir::new("patches"));
+    common::fake_toolchain(td.path());
+    let patches = td.path().join("patches");
+    common::write(&patches.join("0001-fix.patch"), FIX);
+    common::write(&patches.join("0002-fix-of-the-fix.patch"), FIX_OF_THE_FIX);
+
+    // e.g. `FIX` was applied to the checkout by hand
+    let build = build(td.path(), &patches);
+    let popcountsi2 = build.src.join("lib/builtins/popcountsi2.c");
+    common::write(&popcountsi2, "popcountsi2.c\nfix\n");
+
+    build.build.compile();
+    assert_eq!(common::read(&build.out.join("patched/lib/builtins/popcountsi2.c")),
+               "popcountsi2.c\nfix of the fix\n");
+    assert_eq!(common::read(&popcountsi2), "popcountsi2.c\nfix\n");
+}
+
+#[test]
+#[should_panic(expected = "0002-another-fix.patch doesn't apply to the compiler-rt sources
+the sources already had: 0001-fix.patch")]
+fn a_conflict_lists_the_patches_the_sources_already_had() {
+    let td = try!(TempDir::new("patches"));
+    common::fake_toolchain(td.path());
+    let patches = td.path().join("patches");
+    common::write(&patches.join("0001-fix.patch"), FIX);
+    common::write(&patches.join("0002-another-fix.patch"), ANOTHER_FIX);
+
+    let build = build(td.path(), &patches);
+    common::write(&build.src.join("lib/builtins/popcountsi2.c"), "popcountsi2.c\nfix\n");
+
+    build.build.compile();
+}
+
+struct Fixture {
+    build: Build,
+    src: PathBuf,
+    out: PathBuf,
+}
+
+/// A build, in `td`, of a checkout that only contains the placeholder sources of a couple of
+/// intrinsics, patched with the patches in `patches`
+fn build(td: &Path, patches: &Path) -> Fixture {
+    let src = td.join("compiler-rt");
+    let out = td.join("out");
+
+    let mut build = Build::new(Target::from_spec(Path::new("../cortex-m3.json")));
+    build.src(&src)
+        .out_dir(&out)
+        .host("x86_64-unknown-linux-gnu")
+        .opt_level("s")
+        .debug(false)
+        .release(false)
+        .symbols(vec!["__popcountsi2".to_owned(), "__divdi3".to_owned()])
+        .missing_symbols(MissingSymbols::Warn)
+        .patches(patches);
+    common::fake_tools(&mut build, &td.join("bin"));
+
+    for source in build.plan().included() {
+        common::write(&src.join("lib/builtins").join(source), &format!("{}\n", source));
+    }
+    common::fake_objects(&td.join("bin"), &build.plan());
+
+    Fixture {
+        build,
+        src,
+        out,
+    }
+}
@@ -0,0 +1,85 @@
+//! Tests of the placement of each assembly routine in its own section
+//!
+//! Like the incremental tests, the sources are placeholders and the tools are the fake ones of the
+//! snapshot tests.
+
+#![cfg(unix)]
+
+extern crate compiler_rt_build;
+extern crate tempdir;
+
+use std::path::Path;
+
+use compiler_rt_build::{Build, MissingSymbols, Target};
+use tempdir::TempDir;
+
+#[macro_use]
+mod common;
+
+#[test]
+fn assembly_routines_are_split_in_a_copy() {
+    let td = try!(TempDir::new("sections"));
+    let bin = common::fake_toolchain(td.path());
+    let src = td.path().join("compiler-rt");
+    let out = td.path().join("out");
+
+    let build = |function_sections: bool| {
+        let mut build = Build::new(Target::from_spec(Path::new("../cortex-m3.json")));
+        build.src(&src)
+            .out_dir(&out)
+            .host("x86_64-unknown-linux-gnu")
+            .opt_level("s")
+            .debug(false)
+            .release(false)
+            .symbols(vec!["__aeabi_fcmpeq".to_owned()])
+            .function_sections(function_sections)
+            .missing_symbols(MissingSymbols::Warn);
+        common::fake_tools(&mut build, &bin);
+        build
+    };
+
+    let fcmp = "arm/aeabi_fcmp.S";
+    let original = "#include \"../assembly.h\"\n\
+                    DEFINE_COMPILERRT_FUNCTION(__aeabi_fcmpeq)\n\
+                    DEFINE_COMPILERRT_FUNCTION(__aeabi_fcmplt)\n";
+    let split = "#include \"../assembly.h\"\n\
+                 .section .text.__aeabi_fcmpeq,\"ax\",%progbits\n\
+                 .p2align 2\n\
+                 DEFINE_COMPILERRT_FUNCTION(__aeabi_fcmpeq)\n\
+                 .section .text.__aeabi_fcmplt,\"ax\",%progbits\n\
+                 .p2align 2\n\
+                 DEFINE_COMPILERRT_FUNCTION(__aeabi_fcmplt)\n";
+
+    let plan = build(true);
+    let plan = plan.plan();
+    assert!(plan.included().contains(&fcmp));
+    for source in plan.included() {
+        common::write(&src.join("lib/builtins").join(source), source);
+    }
+    common::write(&src.join("lib/builtins").join(fcmp), original);
+    common::fake_objects(&bin, &plan);
+
+    let compiled = |function_sections: bool| {
+        let mut compiled = build(function_sections).compile().compiled;
+        compiled.sort();
+        compiled
+    };
+
+    // The copy is compiled, with the directory of the source in the include path
+    let all = compiled(true);
+    assert!(all.contains(&fcmp));
+    let copy = out.join("sections").join(fcmp);
+    let invocations = common::read(&bin.join("invocations"));
+    assert!(invocations.contains(&format!("-I {} ", src.join("lib/builtins/arm").display())));
+    assert!(invocations.contains(&format!("-c {} -o", copy.display())));
+
+    // Rebuilding doesn't split the routines again nor modifies the checkout
+    assert_eq!(compiled(true), Vec::<&str>::new());
+    assert_eq!(common::read(&copy), split);
+    assert_eq!(common::read(&src.join("lib/builtins").join(fcmp)), original);
+
+    // Only the assembly sources depend on the setting
+    let assembly = all.iter().filter(|source| source.ends_with(".S")).cloned().collect::<Vec<_>>();
+    assert_eq!(compiled(false), assembly);
+    assert_eq!(compiled(true), assembly);
+}
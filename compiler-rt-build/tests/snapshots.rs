@@ -0,0 +1,230 @@
+//! Golden tests of the target resolution and source selection
+//!
+//! Every target specification file in the root of the repository is planned and built with fake
+//! `arm-none-eabi-gcc` and `arm-none-eabi-ar` tools that record how they were invoked; the objects
+//! they "compile" are ELF objects that define the symbols the real ones would define. The
+//! explanation of the plan (the format of `COMPILER_RT_EXPLAIN`) is compared against
+//! `tests/snapshots/$target.txt`, the recorded invocations against the build output and the
+//! symbols of the archive against the symbols of the included sources.
+//!
+//! No compiler-rt checkout or cross toolchain is needed. After changing the rules or the flags on
+//! purpose, run `UPDATE_SNAPSHOTS=1 cargo test -p compiler-rt-build --test snapshots` and review the
+//! diff of the snapshots.
+
+#![cfg(unix)]
+
+extern crate compiler_rt_build;
+extern crate tempdir;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use compiler_rt_build::{Build, MissingSymbols, Output, Profile, Target};
+use tempdir::TempDir;
+
+#[macro_use]
+mod common;
+
+/// Library calls the ARMv6-M archives are reported to lack
+const V6M_MISSING: &[&str] = &["__aeabi_dcmpeq",
+                               "__aeabi_dcmpge",
+                               "__aeabi_dcmpgt",
+                               "__aeabi_dcmple",
+                               "__aeabi_dcmplt",
+                               "__aeabi_fcmpeq",
+                               "__aeabi_fcmpge",
+                               "__aeabi_fcmpgt",
+                               "__aeabi_fcmple",
+                               "__aeabi_fcmplt",
+                               "__aeabi_ldivmod",
+                               "__aeabi_uldivmod"];
+
+/// Variable that makes the test overwrite the snapshots instead of checking them
+const UPDATE: &str = "UPDATE_SNAPSHOTS";
+
+#[test]
+fn targets() {
+    let td = try!(TempDir::new("snapshots"));
+    let bin = common::fake_toolchain(td.path());
+
+    let update = env::var_os(UPDATE).is_some();
+    let mut mismatches = vec![];
+    for target in specs() {
+        let name = target.file_stem().unwrap().to_string_lossy().into_owned();
+        let explanation = explain(&target, &bin, &td.path().join(&name));
+        let snapshot = Path::new("tests/snapshots").join(format!("{}.txt", name));
+
+        if update {
+            try!(fs::create_dir_all(snapshot.parent().unwrap()));
+            try!(try!(File::create(&snapshot)).write_all(explanation.as_bytes()));
+            continue;
+        }
+
+        let expected = &mut String::new();
+        if let Ok(mut f) = File::open(&snapshot) {
+            try!(f.read_to_string(expected));
+        }
+
+        if *expected != explanation {
+            mismatches.push(format!("{}:\n{}", snapshot.display(), diff(expected, &explanation)));
+        }
+    }
+
+    assert!(mismatches.is_empty(),
+            "the build of some targets changed; if that's intended, rerun with {}=1\n\n{}",
+            UPDATE,
+            mismatches.join("\n"));
+}
+
+/// The target specification files in the root of the repository
+fn specs() -> Vec<PathBuf> {
+    let root = Path::new("..");
+    let mut specs = try!(fs::read_dir(root))
+        .map(|entry| try!(entry).path())
+        .filter(|path| path.extension().map(|ext| ext == "json") == Some(true))
+        .collect::<Vec<_>>();
+    specs.sort();
+
+    assert!(!specs.is_empty(),
+            "no target specification files in {}",
+            try!(root.canonicalize()).display());
+    specs
+}
+
+/// Builds the target specified by `spec` with the fake tools in `bin`, in the `td` directory, and
+/// returns the explanation of the build
+fn explain(spec: &Path, bin: &Path, td: &Path) -> String {
+    let target = Target::from_spec(spec);
+
+    // NOTE the fake tools are passed to the build explicitly; these are the ones it would have
+    // derived from the `linker` field
+    let variable = |tool: &str| format!("{}_{}", tool, target.name.replace("-", "_"));
+    if target.name != "no-linker-field" && env::var_os(variable("CC")).is_none() &&
+       env::var_os(variable("AR")).is_none() {
+        assert_eq!(target.tool("CC", "gcc"), "arm-none-eabi-gcc");
+        assert_eq!(target.tool("AR", "ar"), "arm-none-eabi-ar");
+    }
+
+    // NOTE the sources themselves are never read by the fake compiler; as they don't exist, every
+    // object is compiled
+    let src = td.join("compiler-rt");
+    try!(fs::create_dir_all(&src));
+
+    let mut build = Build::new(target);
+    build.src(&src)
+        .out_dir(td.join("out"))
+        .host("x86_64-unknown-linux-gnu")
+        .opt_level("0")
+        .debug(false)
+        .release(false)
+        .jobs(4);
+    common::fake_tools(&mut build, bin);
+
+    // NOTE like CI, only the ARMv6-M builds tolerate missing library calls
+    let v6m = build.target().properties().profile == Profile::V6m;
+    if v6m {
+        build.missing_symbols(MissingSymbols::Warn);
+    }
+
+    // NOTE the tools are shared with the builds of the other targets
+    let invocations = bin.join("invocations");
+    if invocations.exists() {
+        try!(fs::remove_file(&invocations));
+    }
+
+    let plan = build.plan();
+    // NOTE the locations of the fake tools and of the sources change from run to run
+    let explanation = plan.explain()
+        .replace(&format!("{}/", bin.display()), "")
+        .replace(&src.display().to_string(), "$SRC");
+
+    // Planning and explaining don't invoke any tool
+    assert!(!invocations.exists(), "{}", common::read(&invocations));
+
+    common::fake_objects(bin, &plan);
+    let archiver = plan.archiver().to_owned();
+    let mut symbols = plan.symbols();
+    let output = plan.compile();
+
+    check(&common::read(&invocations), &src, &archiver, &output);
+
+    // The archive defines what the objects define, according to its manifest
+    symbols.sort();
+    symbols.dedup();
+    assert_eq!(output.symbols, symbols);
+
+    // The ARMv6-M archives lack the routines compiler-rt only implements in Thumb-2 assembly
+    let mut missing = output.missing
+        .iter()
+        .map(|reason| reason.split('`').nth(1).unwrap_or(reason))
+        .collect::<Vec<_>>();
+    missing.sort();
+    let expected: &[&str] = if v6m { V6M_MISSING } else { &[] };
+    assert_eq!(missing, expected);
+
+    explanation
+}
+
+/// Checks that every included source, and nothing else, was compiled with the same flags and that
+/// all the objects were archived
+///
+/// NOTE the sources are compiled in parallel so they may have been compiled in any order, but the
+/// archive must have been created last
+fn check(invocations: &str, src: &Path, archiver: &str, output: &Output) {
+    let out = output.archive.parent().unwrap();
+    let objects = output.included
+        .iter()
+        .map(|source| out.join(source).with_extension("o"))
+        .collect::<Vec<_>>();
+
+    let mut expected = output.included
+        .iter()
+        .zip(&objects)
+        .map(|(source, object)| {
+            format!("{} {} -c {} -o {}",
+                    output.compiler.display(),
+                    output.flags.join(" "),
+                    src.join("lib/builtins").join(source).display(),
+                    object.display())
+        })
+        .collect::<Vec<_>>();
+
+    expected.sort();
+
+    let mut objects = objects.iter().collect::<Vec<_>>();
+    objects.sort_by_key(|object| object.file_name());
+    expected.push(format!("{} crsD {} {}",
+                          archiver,
+                          output.archive.display(),
+                          objects.iter()
+                              .map(|object| object.display().to_string())
+                              .collect::<Vec<_>>()
+                              .join(" ")));
+
+    // NOTE the compiler is first probed for the optional flags
+    let mut invocations = invocations.lines()
+        .filter(|invocation| !invocation.contains("flag_check.c"))
+        .collect::<Vec<_>>();
+    if let Some((_, compilations)) = invocations.split_last_mut() {
+        compilations.sort();
+    }
+    for (invocation, expected) in invocations.iter().zip(&expected) {
+        assert_eq!(invocation, expected);
+    }
+    assert_eq!(invocations.len(), expected.len());
+}
+
+/// Lines that are only in `old` (`-`) or only in `new` (`+`)
+fn diff(old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    old.iter()
+        .filter(|line| !new.contains(line))
+        .map(|line| format!("-{}", line))
+        .chain(new.iter().filter(|line| !old.contains(line)).map(|line| format!("+{}", line)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,149 @@
+//! The ARM run-time ABI names of the intrinsics
+//!
+//! These always use the base (soft-float) procedure call standard, even on hard-float targets.
+
+use core::arch::global_asm;
+
+use super::float::*;
+use super::int::*;
+
+macro_rules! aliases {
+    ($($alias:ident = $intrinsic:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)+) => {
+        $(
+            #[no_mangle]
+            pub extern "aapcs" fn $alias($($arg: $ty),*) -> $ret {
+                $intrinsic($($arg),*)
+            }
+        )+
+    }
+}
+
+aliases! {
+    __aeabi_fadd = __addsf3(a: f32, b: f32) -> f32;
+    __aeabi_fsub = __subsf3(a: f32, b: f32) -> f32;
+    __aeabi_fmul = __mulsf3(a: f32, b: f32) -> f32;
+    __aeabi_fdiv = __divsf3(a: f32, b: f32) -> f32;
+    __aeabi_dadd = __adddf3(a: f64, b: f64) -> f64;
+    __aeabi_dsub = __subdf3(a: f64, b: f64) -> f64;
+    __aeabi_dmul = __muldf3(a: f64, b: f64) -> f64;
+    __aeabi_ddiv = __divdf3(a: f64, b: f64) -> f64;
+
+    __aeabi_f2iz = __fixsfsi(a: f32) -> i32;
+    __aeabi_f2uiz = __fixunssfsi(a: f32) -> u32;
+    __aeabi_f2lz = __fixsfdi(a: f32) -> i64;
+    __aeabi_f2ulz = __fixunssfdi(a: f32) -> u64;
+    __aeabi_d2iz = __fixdfsi(a: f64) -> i32;
+    __aeabi_d2uiz = __fixunsdfsi(a: f64) -> u32;
+    __aeabi_d2lz = __fixdfdi(a: f64) -> i64;
+    __aeabi_d2ulz = __fixunsdfdi(a: f64) -> u64;
+    __aeabi_i2f = __floatsisf(a: i32) -> f32;
+    __aeabi_ui2f = __floatunsisf(a: u32) -> f32;
+    __aeabi_l2f = __floatdisf(a: i64) -> f32;
+    __aeabi_ul2f = __floatundisf(a: u64) -> f32;
+    __aeabi_i2d = __floatsidf(a: i32) -> f64;
+    __aeabi_ui2d = __floatunsidf(a: u32) -> f64;
+    __aeabi_l2d = __floatdidf(a: i64) -> f64;
+    __aeabi_ul2d = __floatundidf(a: u64) -> f64;
+    __aeabi_f2d = __extendsfdf2(a: f32) -> f64;
+    __aeabi_d2f = __truncdfsf2(a: f64) -> f32;
+
+    __aeabi_idiv = __divsi3(a: i32, b: i32) -> i32;
+    __aeabi_uidiv = __udivsi3(a: u32, b: u32) -> u32;
+    __aeabi_lmul = __muldi3(a: i64, b: i64) -> i64;
+    __aeabi_llsl = __ashldi3(a: i64, b: i32) -> i64;
+    __aeabi_llsr = __lshrdi3(a: i64, b: i32) -> i64;
+    __aeabi_lasr = __ashrdi3(a: i64, b: i32) -> i64;
+}
+
+// NOTE unlike `__eqsf2` & co. these return a boolean: 1 if the relation holds, 0 otherwise (which
+// includes the case where either operand is NaN)
+macro_rules! comparisons {
+    ($($alias:ident = $module:ident: $F:ident, $($ord:expr),+;)+) => {
+        $(
+            #[no_mangle]
+            pub extern "aapcs" fn $alias(a: $F, b: $F) -> i32 {
+                match $module::cmp(a, b) {
+                    $(Some($ord))|+ => 1,
+                    _ => 0,
+                }
+            }
+        )+
+    }
+}
+
+comparisons! {
+    __aeabi_fcmpeq = single: f32, 0;
+    __aeabi_fcmplt = single: f32, -1;
+    __aeabi_fcmple = single: f32, -1, 0;
+    __aeabi_fcmpge = single: f32, 0, 1;
+    __aeabi_fcmpgt = single: f32, 1;
+    __aeabi_dcmpeq = double: f64, 0;
+    __aeabi_dcmplt = double: f64, -1;
+    __aeabi_dcmple = double: f64, -1, 0;
+    __aeabi_dcmpge = double: f64, 0, 1;
+    __aeabi_dcmpgt = double: f64, 1;
+}
+
+#[no_mangle]
+pub extern "aapcs" fn __aeabi_fcmpun(a: f32, b: f32) -> i32 {
+    __unordsf2(a, b)
+}
+
+#[no_mangle]
+pub extern "aapcs" fn __aeabi_dcmpun(a: f64, b: f64) -> i32 {
+    __unorddf2(a, b)
+}
+
+// The division routines return both the quotient (in r0 or r0:r1) and the remainder (in r1 or
+// r2:r3), which can't be expressed in Rust. The remainder is passed through the stack.
+global_asm!("
+    .syntax unified
+
+    .global __aeabi_uidivmod
+    .type __aeabi_uidivmod, %function
+__aeabi_uidivmod:
+    push {lr}
+    sub sp, sp, #4
+    mov r2, sp
+    bl __udivmodsi4
+    ldr r1, [sp]
+    add sp, sp, #4
+    pop {pc}
+
+    .global __aeabi_idivmod
+    .type __aeabi_idivmod, %function
+__aeabi_idivmod:
+    push {lr}
+    sub sp, sp, #4
+    mov r2, sp
+    bl __divmodsi4
+    ldr r1, [sp]
+    add sp, sp, #4
+    pop {pc}
+
+    .global __aeabi_uldivmod
+    .type __aeabi_uldivmod, %function
+__aeabi_uldivmod:
+    push {r4, lr}
+    sub sp, sp, #16
+    add r4, sp, #8
+    str r4, [sp]
+    bl __udivmoddi4
+    ldr r2, [sp, #8]
+    ldr r3, [sp, #12]
+    add sp, sp, #16
+    pop {r4, pc}
+
+    .global __aeabi_ldivmod
+    .type __aeabi_ldivmod, %function
+__aeabi_ldivmod:
+    push {r4, lr}
+    sub sp, sp, #16
+    add r4, sp, #8
+    str r4, [sp]
+    bl __divmoddi4
+    ldr r2, [sp, #8]
+    ldr r3, [sp, #12]
+    add sp, sp, #16
+    pop {r4, pc}
+");
@@ -0,0 +1,591 @@
+//! Soft-float intrinsics, after compiler-rt's `fp_add_impl.inc`, `fp_mul_impl.inc`, etc.
+
+use super::{clz32, clz64, wmul32, wmul64};
+
+fn wmul32_parts(a: u32, b: u32) -> (u32, u32) {
+    let p = wmul32(a, b);
+    ((p >> 32) as u32, p as u32)
+}
+
+macro_rules! soft_float {
+    ($module:ident, $F:ident, $U:ident, $I:ident, $sig_bits:expr, $clz:ident, $wmul:ident) => {
+        pub mod $module {
+            use super::*;
+
+            pub type F = $F;
+            pub type U = $U;
+            pub type I = $I;
+
+            pub const BITS: u32 = 8 * ::core::mem::size_of::<U>() as u32;
+            pub const SIG_BITS: u32 = $sig_bits;
+            pub const EXP_BITS: u32 = BITS - SIG_BITS - 1;
+            pub const MAX_EXP: i32 = (1 << EXP_BITS) - 1;
+            pub const BIAS: i32 = MAX_EXP >> 1;
+
+            pub const IMPLICIT: U = 1 << SIG_BITS;
+            pub const SIG_MASK: U = IMPLICIT - 1;
+            pub const SIGN: U = 1 << (BITS - 1);
+            pub const ABS_MASK: U = SIGN - 1;
+            pub const INF: U = ABS_MASK ^ SIG_MASK;
+            pub const QUIET: U = IMPLICIT >> 1;
+            pub const QNAN: U = INF | QUIET;
+
+            /// Shifts the significand of a subnormal number so that its leading bit is where the
+            /// implicit bit goes and returns the adjustment for the exponent
+            fn normalize(sig: &mut U) -> i32 {
+                let shift = $clz(*sig) - $clz(IMPLICIT);
+                *sig <<= shift;
+                1 - shift as i32
+            }
+
+            /// Rounds (to nearest, ties to even) and packs a result whose significand, `sig`, has
+            /// its leading bit at `IMPLICIT << 3` followed by the round and guard bits and a
+            /// sticky bit
+            fn round_pack(sign: U, mut exp: i32, mut sig: U) -> F {
+                if exp >= MAX_EXP {
+                    return F::from_bits(INF | sign);
+                }
+
+                if exp <= 0 {
+                    // Subnormal result
+                    let shift = (1 - exp) as u32;
+                    sig = if shift < BITS {
+                        let sticky = (sig << (BITS - shift) != 0) as U;
+                        sig >> shift | sticky
+                    } else {
+                        (sig != 0) as U
+                    };
+                    exp = 0;
+                }
+
+                let rgs = sig & 7;
+                let mut result = (sig >> 3 & SIG_MASK) | (exp as U) << SIG_BITS | sign;
+
+                if rgs > 4 {
+                    result += 1;
+                } else if rgs == 4 {
+                    result += result & 1;
+                }
+
+                F::from_bits(result)
+            }
+
+            pub fn add(a: F, b: F) -> F {
+                let mut a_rep = a.to_bits();
+                let mut b_rep = b.to_bits();
+                let a_abs = a_rep & ABS_MASK;
+                let b_abs = b_rep & ABS_MASK;
+
+                // Zero, infinity or NaN
+                if a_abs.wrapping_sub(1) >= INF - 1 || b_abs.wrapping_sub(1) >= INF - 1 {
+                    if a_abs > INF {
+                        return F::from_bits(a_rep | QUIET);
+                    }
+                    if b_abs > INF {
+                        return F::from_bits(b_rep | QUIET);
+                    }
+                    if a_abs == INF {
+                        // inf + -inf = NaN
+                        return if a_rep ^ b_rep == SIGN { F::from_bits(QNAN) } else { a };
+                    }
+                    if b_abs == INF {
+                        return b;
+                    }
+                    if a_abs == 0 {
+                        // -0 + -0 = -0
+                        return if b_abs == 0 { F::from_bits(a_rep & b_rep) } else { b };
+                    }
+                    if b_abs == 0 {
+                        return a;
+                    }
+                }
+
+                if b_abs > a_abs {
+                    let tmp = a_rep;
+                    a_rep = b_rep;
+                    b_rep = tmp;
+                }
+
+                let mut a_exp = (a_rep >> SIG_BITS) as i32 & MAX_EXP;
+                let mut b_exp = (b_rep >> SIG_BITS) as i32 & MAX_EXP;
+                let mut a_sig = a_rep & SIG_MASK;
+                let mut b_sig = b_rep & SIG_MASK;
+
+                if a_exp == 0 {
+                    a_exp = normalize(&mut a_sig);
+                }
+                if b_exp == 0 {
+                    b_exp = normalize(&mut b_sig);
+                }
+
+                let sign = a_rep & SIGN;
+                let subtraction = (a_rep ^ b_rep) & SIGN != 0;
+
+                // 3 extra bits for rounding
+                a_sig = (a_sig | IMPLICIT) << 3;
+                b_sig = (b_sig | IMPLICIT) << 3;
+
+                let align = (a_exp - b_exp) as u32;
+                if align != 0 {
+                    b_sig = if align < BITS {
+                        let sticky = (b_sig << (BITS - align) != 0) as U;
+                        b_sig >> align | sticky
+                    } else {
+                        1
+                    };
+                }
+
+                if subtraction {
+                    a_sig -= b_sig;
+                    if a_sig == 0 {
+                        return F::from_bits(0);
+                    }
+
+                    if a_sig < IMPLICIT << 3 {
+                        let shift = $clz(a_sig) - $clz(IMPLICIT << 3);
+                        a_sig <<= shift;
+                        a_exp -= shift as i32;
+                    }
+                } else {
+                    a_sig += b_sig;
+
+                    if a_sig & IMPLICIT << 4 != 0 {
+                        let sticky = a_sig & 1;
+                        a_sig = a_sig >> 1 | sticky;
+                        a_exp += 1;
+                    }
+                }
+
+                round_pack(sign, a_exp, a_sig)
+            }
+
+            pub fn sub(a: F, b: F) -> F {
+                add(a, F::from_bits(b.to_bits() ^ SIGN))
+            }
+
+            pub fn mul(a: F, b: F) -> F {
+                let a_rep = a.to_bits();
+                let b_rep = b.to_bits();
+                let a_abs = a_rep & ABS_MASK;
+                let b_abs = b_rep & ABS_MASK;
+                let sign = (a_rep ^ b_rep) & SIGN;
+
+                let a_exp = (a_rep >> SIG_BITS) as i32 & MAX_EXP;
+                let b_exp = (b_rep >> SIG_BITS) as i32 & MAX_EXP;
+                let mut a_sig = a_rep & SIG_MASK;
+                let mut b_sig = b_rep & SIG_MASK;
+                let mut scale = 0;
+
+                // Zero, subnormal, infinity or NaN
+                if a_exp.wrapping_sub(1) as u32 >= (MAX_EXP - 1) as u32 ||
+                   b_exp.wrapping_sub(1) as u32 >= (MAX_EXP - 1) as u32 {
+                    if a_abs > INF {
+                        return F::from_bits(a_rep | QUIET);
+                    }
+                    if b_abs > INF {
+                        return F::from_bits(b_rep | QUIET);
+                    }
+                    if a_abs == INF {
+                        // inf * 0 = NaN
+                        return F::from_bits(if b_abs != 0 { INF | sign } else { QNAN });
+                    }
+                    if b_abs == INF {
+                        return F::from_bits(if a_abs != 0 { INF | sign } else { QNAN });
+                    }
+                    if a_abs == 0 || b_abs == 0 {
+                        return F::from_bits(sign);
+                    }
+
+                    if a_abs < IMPLICIT {
+                        scale += normalize(&mut a_sig);
+                    }
+                    if b_abs < IMPLICIT {
+                        scale += normalize(&mut b_sig);
+                    }
+                }
+
+                a_sig |= IMPLICIT;
+                b_sig |= IMPLICIT;
+
+                // The product has its leading bit at `2 * SIG_BITS` or at `2 * SIG_BITS + 1`
+                let (hi, lo) = $wmul(a_sig, b_sig);
+                let mut exp = a_exp + b_exp - BIAS + scale;
+                let shift = if hi & (1 << (2 * SIG_BITS + 1 - BITS)) != 0 {
+                    exp += 1;
+                    SIG_BITS - 2
+                } else {
+                    SIG_BITS - 3
+                };
+
+                let sticky = (lo << (BITS - shift) != 0) as U;
+                let sig = hi << (BITS - shift) | lo >> shift | sticky;
+
+                round_pack(sign, exp, sig)
+            }
+
+            pub fn div(a: F, b: F) -> F {
+                let a_rep = a.to_bits();
+                let b_rep = b.to_bits();
+                let a_abs = a_rep & ABS_MASK;
+                let b_abs = b_rep & ABS_MASK;
+                let sign = (a_rep ^ b_rep) & SIGN;
+
+                let a_exp = (a_rep >> SIG_BITS) as i32 & MAX_EXP;
+                let b_exp = (b_rep >> SIG_BITS) as i32 & MAX_EXP;
+                let mut a_sig = a_rep & SIG_MASK;
+                let mut b_sig = b_rep & SIG_MASK;
+                let mut scale = 0;
+
+                // Zero, subnormal, infinity or NaN
+                if a_exp.wrapping_sub(1) as u32 >= (MAX_EXP - 1) as u32 ||
+                   b_exp.wrapping_sub(1) as u32 >= (MAX_EXP - 1) as u32 {
+                    if a_abs > INF {
+                        return F::from_bits(a_rep | QUIET);
+                    }
+                    if b_abs > INF {
+                        return F::from_bits(b_rep | QUIET);
+                    }
+                    if a_abs == INF {
+                        // inf / inf = NaN
+                        return F::from_bits(if b_abs == INF { QNAN } else { INF | sign });
+                    }
+                    if b_abs == INF {
+                        return F::from_bits(sign);
+                    }
+                    if a_abs == 0 {
+                        // 0 / 0 = NaN
+                        return F::from_bits(if b_abs == 0 { QNAN } else { sign });
+                    }
+                    if b_abs == 0 {
+                        return F::from_bits(INF | sign);
+                    }
+
+                    if a_abs < IMPLICIT {
+                        scale += normalize(&mut a_sig);
+                    }
+                    if b_abs < IMPLICIT {
+                        scale -= normalize(&mut b_sig);
+                    }
+                }
+
+                a_sig |= IMPLICIT;
+                b_sig |= IMPLICIT;
+
+                let mut exp = a_exp - b_exp + BIAS + scale;
+                if a_sig < b_sig {
+                    a_sig <<= 1;
+                    exp -= 1;
+                }
+
+                // Long division, one quotient bit at a time: the leading (integer) bit, the
+                // `SIG_BITS` fraction bits and the 3 extra bits `round_pack` expects
+                let mut q: U = 0;
+                let mut r = a_sig;
+                for _ in 0..SIG_BITS + 4 {
+                    q <<= 1;
+                    if r >= b_sig {
+                        r -= b_sig;
+                        q |= 1;
+                    }
+                    r <<= 1;
+                }
+
+                round_pack(sign, exp, q | (r != 0) as U)
+            }
+
+            /// -1 if `a < b`, 0 if `a == b`, 1 if `a > b` and `None` if either is NaN
+            pub fn cmp(a: F, b: F) -> Option<i32> {
+                let a_int = a.to_bits() as I;
+                let b_int = b.to_bits() as I;
+                let a_abs = a.to_bits() & ABS_MASK;
+                let b_abs = b.to_bits() & ABS_MASK;
+
+                if a_abs > INF || b_abs > INF {
+                    return None;
+                }
+
+                // +0 == -0
+                if a_abs | b_abs == 0 {
+                    return Some(0);
+                }
+
+                // NOTE The representations of floats compare like sign-magnitude integers
+                Some(if a_int & b_int >= 0 {
+                    if a_int < b_int { -1 } else if a_int == b_int { 0 } else { 1 }
+                } else {
+                    if a_int > b_int { -1 } else if a_int == b_int { 0 } else { 1 }
+                })
+            }
+
+            /// Converts the integer `-u` (if `negative`) or `u` to a float, rounding to nearest
+            pub fn from_int(negative: bool, u: u64) -> F {
+                if u == 0 {
+                    return F::from_bits(0);
+                }
+
+                let sign = if negative { SIGN } else { 0 };
+                let n = 64 - clz64(u);
+                let exp = n as i32 - 1 + BIAS;
+
+                // Move the leading bit to `IMPLICIT << 3`, keeping a sticky bit
+                let sig = if n > SIG_BITS + 4 {
+                    let shift = n - (SIG_BITS + 4);
+                    let sticky = (u << (64 - shift) != 0) as u64;
+                    u >> shift | sticky
+                } else {
+                    u << (SIG_BITS + 4 - n)
+                };
+
+                round_pack(sign, exp, sig as U)
+            }
+
+            /// Converts `a` to an integer of `bits` bits, truncating towards zero and saturating
+            /// at the bounds of the integer type
+            pub fn to_int(a: F, bits: u32, signed: bool) -> u64 {
+                let rep = a.to_bits();
+                let negative = rep & SIGN != 0;
+                let exp = ((rep & ABS_MASK) >> SIG_BITS) as i32 - BIAS;
+                let sig = ((rep & SIG_MASK) | IMPLICIT) as u64;
+
+                let max: u64 = if signed { (1 << (bits - 1)) - 1 } else { !0 >> (64 - bits) };
+                let min = if signed { (max + 1).wrapping_neg() } else { 0 };
+
+                if exp < 0 {
+                    return 0;
+                }
+                if negative && !signed {
+                    return 0;
+                }
+                if exp as u32 >= bits - signed as u32 {
+                    return if negative { min } else { max };
+                }
+
+                let magnitude = if exp < SIG_BITS as i32 {
+                    sig >> (SIG_BITS as i32 - exp)
+                } else {
+                    sig << (exp - SIG_BITS as i32)
+                };
+
+                if negative { magnitude.wrapping_neg() } else { magnitude }
+            }
+        }
+    }
+}
+
+soft_float!(single, f32, u32, i32, 23, clz32, wmul32_parts);
+soft_float!(double, f64, u64, i64, 52, clz64, wmul64);
+
+macro_rules! arith {
+    ($($name:ident = $module:ident::$op:ident: $F:ident;)+) => {
+        $(
+            #[no_mangle]
+            pub extern "C" fn $name(a: $F, b: $F) -> $F {
+                $module::$op(a, b)
+            }
+        )+
+    }
+}
+
+arith! {
+    __addsf3 = single::add: f32;
+    __subsf3 = single::sub: f32;
+    __mulsf3 = single::mul: f32;
+    __divsf3 = single::div: f32;
+    __adddf3 = double::add: f64;
+    __subdf3 = double::sub: f64;
+    __muldf3 = double::mul: f64;
+    __divdf3 = double::div: f64;
+}
+
+// NOTE The `__le*`, `__lt*`, `__eq*` and `__ne*` comparisons return 1 for NaNs; the `__ge*` and
+// `__gt*` ones return -1. That way `__ltsf2(a, b) < 0` and `__gesf2(a, b) >= 0` are false if either
+// operand is NaN
+macro_rules! cmp {
+    ($($name:ident = $module:ident: $F:ident, $unordered:expr;)+) => {
+        $(
+            #[no_mangle]
+            pub extern "C" fn $name(a: $F, b: $F) -> i32 {
+                $module::cmp(a, b).unwrap_or($unordered)
+            }
+        )+
+    }
+}
+
+cmp! {
+    __eqsf2 = single: f32, 1;
+    __nesf2 = single: f32, 1;
+    __ltsf2 = single: f32, 1;
+    __lesf2 = single: f32, 1;
+    __gtsf2 = single: f32, -1;
+    __gesf2 = single: f32, -1;
+    __eqdf2 = double: f64, 1;
+    __nedf2 = double: f64, 1;
+    __ltdf2 = double: f64, 1;
+    __ledf2 = double: f64, 1;
+    __gtdf2 = double: f64, -1;
+    __gedf2 = double: f64, -1;
+}
+
+#[no_mangle]
+pub extern "C" fn __unordsf2(a: f32, b: f32) -> i32 {
+    single::cmp(a, b).is_none() as i32
+}
+
+#[no_mangle]
+pub extern "C" fn __unorddf2(a: f64, b: f64) -> i32 {
+    double::cmp(a, b).is_none() as i32
+}
+
+macro_rules! powi {
+    ($($name:ident = $module:ident: $F:ident;)+) => {
+        $(
+            /// `a` raised to the `b`-th power, by repeated squaring
+            #[no_mangle]
+            pub extern "C" fn $name(a: $F, b: i32) -> $F {
+                let mut n = if b < 0 { (b as u32).wrapping_neg() } else { b as u32 };
+                let mut a = a;
+                let mut r = 1.;
+
+                loop {
+                    if n & 1 != 0 {
+                        r = $module::mul(r, a);
+                    }
+                    n >>= 1;
+                    if n == 0 {
+                        break;
+                    }
+                    a = $module::mul(a, a);
+                }
+
+                if b < 0 { $module::div(1., r) } else { r }
+            }
+        )+
+    }
+}
+
+powi! {
+    __powisf2 = single: f32;
+    __powidf2 = double: f64;
+}
+
+macro_rules! from_int {
+    ($($name:ident = $module:ident: $ity:ident -> $F:ident, $signed:expr;)+) => {
+        $(
+            #[no_mangle]
+            pub extern "C" fn $name(a: $ity) -> $F {
+                if $signed && a < 0 as $ity {
+                    $module::from_int(true, (a as i64).wrapping_neg() as u64)
+                } else {
+                    $module::from_int(false, a as u64)
+                }
+            }
+        )+
+    }
+}
+
+from_int! {
+    __floatsisf = single: i32 -> f32, true;
+    __floatdisf = single: i64 -> f32, true;
+    __floatunsisf = single: u32 -> f32, false;
+    __floatundisf = single: u64 -> f32, false;
+    __floatsidf = double: i32 -> f64, true;
+    __floatdidf = double: i64 -> f64, true;
+    __floatunsidf = double: u32 -> f64, false;
+    __floatundidf = double: u64 -> f64, false;
+}
+
+macro_rules! to_int {
+    ($($name:ident = $module:ident: $F:ident -> $ity:ident, $bits:expr, $signed:expr;)+) => {
+        $(
+            #[no_mangle]
+            pub extern "C" fn $name(a: $F) -> $ity {
+                $module::to_int(a, $bits, $signed) as $ity
+            }
+        )+
+    }
+}
+
+to_int! {
+    __fixsfsi = single: f32 -> i32, 32, true;
+    __fixsfdi = single: f32 -> i64, 64, true;
+    __fixunssfsi = single: f32 -> u32, 32, false;
+    __fixunssfdi = single: f32 -> u64, 64, false;
+    __fixdfsi = double: f64 -> i32, 32, true;
+    __fixdfdi = double: f64 -> i64, 64, true;
+    __fixunsdfsi = double: f64 -> u32, 32, false;
+    __fixunsdfdi = double: f64 -> u64, 64, false;
+}
+
+#[no_mangle]
+pub extern "C" fn __extendsfdf2(a: f32) -> f64 {
+    let rep = a.to_bits();
+    let abs = rep & single::ABS_MASK;
+    let sign = ((rep & single::SIGN) as u64) << 32;
+    let delta = (double::BIAS - single::BIAS) as u64;
+    let shift = double::SIG_BITS - single::SIG_BITS;
+
+    let abs = if abs.wrapping_sub(single::IMPLICIT) < single::INF - single::IMPLICIT {
+        // Normal
+        ((abs as u64) << shift) + (delta << double::SIG_BITS)
+    } else if abs >= single::INF {
+        // Infinity or NaN
+        double::INF | ((abs & single::SIG_MASK) as u64) << shift
+    } else if abs != 0 {
+        // Subnormal; normal in the wider format
+        let scale = clz32(abs) - clz32(single::IMPLICIT);
+        ((abs as u64) << (shift + scale) ^ double::IMPLICIT) |
+        (delta - scale as u64 + 1) << double::SIG_BITS
+    } else {
+        0
+    };
+
+    f64::from_bits(abs | sign)
+}
+
+#[no_mangle]
+pub extern "C" fn __truncdfsf2(a: f64) -> f32 {
+    let rep = a.to_bits();
+    let abs = rep & double::ABS_MASK;
+    let sign = ((rep & double::SIGN) >> 32) as u32;
+    let delta = double::BIAS - single::BIAS;
+    let shift = double::SIG_BITS - single::SIG_BITS;
+    let round_mask = (1 << shift) - 1;
+    let halfway = 1 << (shift - 1);
+
+    let underflow = ((delta + 1) as u64) << double::SIG_BITS;
+    let overflow = ((delta + single::MAX_EXP) as u64) << double::SIG_BITS;
+
+    let round = |abs_result: u32, round_bits: u64| if round_bits > halfway {
+        abs_result + 1
+    } else if round_bits == halfway {
+        abs_result + (abs_result & 1)
+    } else {
+        abs_result
+    };
+
+    let abs_result = if abs.wrapping_sub(underflow) < abs.wrapping_sub(overflow) {
+        // Normal in both formats
+        let abs_result = (abs >> shift) - ((delta as u64) << single::SIG_BITS);
+        round(abs_result as u32, abs & round_mask)
+    } else if abs > double::INF {
+        // NaN; keep the top bits of the payload and make it quiet
+        single::INF | single::QUIET | ((abs & double::SIG_MASK) >> shift) as u32
+    } else if abs >= overflow {
+        single::INF
+    } else {
+        // Subnormal or zero in the narrower format
+        let exp = (abs >> double::SIG_BITS) as i32;
+        let denormal_shift = (delta - exp + 1) as u32;
+        let sig = (abs & double::SIG_MASK) | double::IMPLICIT;
+
+        if denormal_shift > double::SIG_BITS {
+            0
+        } else {
+            let sticky = (sig << (64 - denormal_shift) != 0) as u64;
+            let denormalized = sig >> denormal_shift | sticky;
+            round((denormalized >> shift) as u32, denormalized & round_mask)
+        }
+    };
+
+    f32::from_bits(abs_result | sign)
+}
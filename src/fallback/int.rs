@@ -0,0 +1,286 @@
+//! Integer intrinsics
+
+use super::{clz32, clz64, clz128, wmul32, wmul64};
+
+macro_rules! udivmod {
+    ($name:ident, $uty:ident, $clz:ident) => {
+        /// Quotient and remainder of `n / d`. Division by zero returns an unspecified quotient
+        fn $name(n: $uty, d: $uty) -> ($uty, $uty) {
+            if d > n {
+                return (0, n);
+            }
+
+            if d == 0 {
+                return (!0, n);
+            }
+
+            let shift = $clz(d) - $clz(n);
+            let mut d = d << shift;
+            let mut q = 0;
+            let mut r = n;
+            for _ in 0..shift + 1 {
+                q <<= 1;
+                if r >= d {
+                    r -= d;
+                    q |= 1;
+                }
+                d >>= 1;
+            }
+
+            (q, r)
+        }
+    }
+}
+
+udivmod!(udivmod32, u32, clz32);
+udivmod!(udivmod64, u64, clz64);
+udivmod!(udivmod128, u128, clz128);
+
+/// Low 64 bits of the product of `a` and `b`
+fn mul64(a: u64, b: u64) -> u64 {
+    let (a_lo, a_hi) = (a as u32, (a >> 32) as u32);
+    let (b_lo, b_hi) = (b as u32, (b >> 32) as u32);
+
+    let cross = a_hi.wrapping_mul(b_lo).wrapping_add(a_lo.wrapping_mul(b_hi));
+    wmul32(a_lo, b_lo).wrapping_add((cross as u64) << 32)
+}
+
+/// Full 256-bit product of `a` and `b`, as `(high, low)` halves
+fn wmul128(a: u128, b: u128) -> (u128, u128) {
+    let (a_lo, a_hi) = (a as u64, (a >> 64) as u64);
+    let (b_lo, b_hi) = (b as u64, (b >> 64) as u64);
+
+    let join = |(hi, lo): (u64, u64)| (hi as u128) << 64 | lo as u128;
+    let lo_lo = join(wmul64(a_lo, b_lo));
+    let hi_lo = join(wmul64(a_hi, b_lo));
+    let lo_hi = join(wmul64(a_lo, b_hi));
+    let hi_hi = join(wmul64(a_hi, b_hi));
+
+    let mask = 0xffff_ffff_ffff_ffff;
+    let mid = (lo_lo >> 64) + (hi_lo & mask) + (lo_hi & mask);
+    let lo = (lo_lo & mask) | (mid << 64);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+
+    (hi, lo)
+}
+
+macro_rules! signed_div {
+    ($div:ident, $mod_:ident, $divmod:ident, $ity:ident, $uty:ident, $udivmod:ident) => {
+        fn $divmod(a: $ity, b: $ity) -> ($ity, $ity) {
+            let ua = if a < 0 { (a as $uty).wrapping_neg() } else { a as $uty };
+            let ub = if b < 0 { (b as $uty).wrapping_neg() } else { b as $uty };
+
+            let (q, r) = $udivmod(ua, ub);
+            // The quotient is negative if the signs differ; the remainder has the sign of `a`
+            let q = if (a < 0) != (b < 0) { q.wrapping_neg() } else { q };
+            let r = if a < 0 { r.wrapping_neg() } else { r };
+
+            (q as $ity, r as $ity)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $div(a: $ity, b: $ity) -> $ity {
+            $divmod(a, b).0
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $mod_(a: $ity, b: $ity) -> $ity {
+            $divmod(a, b).1
+        }
+    }
+}
+
+signed_div!(__divsi3, __modsi3, divmod32, i32, u32, udivmod32);
+signed_div!(__divdi3, __moddi3, divmod64, i64, u64, udivmod64);
+signed_div!(__divti3, __modti3, divmod128, i128, u128, udivmod128);
+
+macro_rules! unsigned_div {
+    ($div:ident, $mod_:ident, $divmod:ident, $uty:ident, $udivmod:ident) => {
+        #[no_mangle]
+        pub extern "C" fn $div(a: $uty, b: $uty) -> $uty {
+            $udivmod(a, b).0
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $mod_(a: $uty, b: $uty) -> $uty {
+            $udivmod(a, b).1
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $divmod(a: $uty, b: $uty, rem: *mut $uty) -> $uty {
+            let (q, r) = $udivmod(a, b);
+            if !rem.is_null() {
+                *rem = r;
+            }
+            q
+        }
+    }
+}
+
+unsigned_div!(__udivsi3, __umodsi3, __udivmodsi4, u32, udivmod32);
+unsigned_div!(__udivdi3, __umoddi3, __udivmoddi4, u64, udivmod64);
+unsigned_div!(__udivti3, __umodti3, __udivmodti4, u128, udivmod128);
+
+#[no_mangle]
+pub unsafe extern "C" fn __divmodsi4(a: i32, b: i32, rem: *mut i32) -> i32 {
+    let (q, r) = divmod32(a, b);
+    *rem = r;
+    q
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn __divmoddi4(a: i64, b: i64, rem: *mut i64) -> i64 {
+    let (q, r) = divmod64(a, b);
+    *rem = r;
+    q
+}
+
+#[no_mangle]
+pub extern "C" fn __muldi3(a: i64, b: i64) -> i64 {
+    mul64(a as u64, b as u64) as i64
+}
+
+#[no_mangle]
+pub extern "C" fn __multi3(a: i128, b: i128) -> i128 {
+    let (a, b) = (a as u128, b as u128);
+    let (a_lo, a_hi) = (a as u64, (a >> 64) as u64);
+    let (b_lo, b_hi) = (b as u64, (b >> 64) as u64);
+
+    let (hi, lo) = wmul64(a_lo, b_lo);
+    let hi = hi.wrapping_add(mul64(a_hi, b_lo)).wrapping_add(mul64(a_lo, b_hi));
+
+    ((hi as u128) << 64 | lo as u128) as i128
+}
+
+macro_rules! mulo {
+    ($name:ident, $ity:ident, $uty:ident, $wmul:expr) => {
+        /// Wrapping product of `a` and `b`; `*overflow` is set to 1 if it doesn't fit in the type
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(a: $ity, b: $ity, overflow: *mut i32) -> $ity {
+            let ua = if a < 0 { (a as $uty).wrapping_neg() } else { a as $uty };
+            let ub = if b < 0 { (b as $uty).wrapping_neg() } else { b as $uty };
+            let negative = (a < 0) != (b < 0) && a != 0 && b != 0;
+
+            let (hi, lo): ($uty, $uty) = $wmul(ua, ub);
+            let max = (!0 as $uty) >> 1;
+            let limit = if negative { max + 1 } else { max };
+
+            *overflow = if hi != 0 || lo > limit { 1 } else { 0 };
+            (if negative { lo.wrapping_neg() } else { lo }) as $ity
+        }
+    }
+}
+
+mulo!(__mulosi4, i32, u32, |a, b| {
+    let p = wmul32(a, b);
+    ((p >> 32) as u32, p as u32)
+});
+mulo!(__mulodi4, i64, u64, wmul64);
+mulo!(__muloti4, i128, u128, wmul128);
+
+macro_rules! shifts {
+    ($ashl:ident, $ashr:ident, $lshr:ident, $ity:ident, $uty:ident, $hty:ident, $hbits:expr) => {
+        #[no_mangle]
+        pub extern "C" fn $ashl(a: $ity, b: i32) -> $ity {
+            let (lo, hi) = (a as $hty, (a as $uty >> $hbits) as $hty);
+            let b = b as u32;
+
+            let (lo, hi) = if b == 0 {
+                (lo, hi)
+            } else if b < $hbits {
+                (lo << b, hi << b | lo >> ($hbits - b))
+            } else {
+                (0, lo << (b - $hbits))
+            };
+
+            ((hi as $uty) << $hbits | lo as $uty) as $ity
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $lshr(a: $ity, b: i32) -> $ity {
+            let (lo, hi) = (a as $hty, (a as $uty >> $hbits) as $hty);
+            let b = b as u32;
+
+            let (lo, hi) = if b == 0 {
+                (lo, hi)
+            } else if b < $hbits {
+                (lo >> b | hi << ($hbits - b), hi >> b)
+            } else {
+                (hi >> (b - $hbits), 0)
+            };
+
+            ((hi as $uty) << $hbits | lo as $uty) as $ity
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $ashr(a: $ity, b: i32) -> $ity {
+            let shifted = $lshr(a, b);
+
+            // Fill the vacated bits with copies of the sign bit
+            if a < 0 && b != 0 {
+                shifted | !((!0 as $uty) >> b as u32) as $ity
+            } else {
+                shifted
+            }
+        }
+    }
+}
+
+shifts!(__ashldi3, __ashrdi3, __lshrdi3, i64, u64, u32, 32);
+shifts!(__ashlti3, __ashrti3, __lshrti3, i128, u128, u64, 64);
+
+#[no_mangle]
+pub extern "C" fn __clzsi2(a: i32) -> i32 {
+    clz32(a as u32) as i32
+}
+
+#[no_mangle]
+pub extern "C" fn __clzdi2(a: i64) -> i32 {
+    clz64(a as u64) as i32
+}
+
+#[no_mangle]
+pub extern "C" fn __clzti2(a: i128) -> i32 {
+    clz128(a as u128) as i32
+}
+
+#[no_mangle]
+pub extern "C" fn __ctzsi2(a: i32) -> i32 {
+    let a = a as u32;
+
+    if a == 0 { 32 } else { 31 - clz32(a & a.wrapping_neg()) as i32 }
+}
+
+#[no_mangle]
+pub extern "C" fn __ctzdi2(a: i64) -> i32 {
+    let a = a as u64;
+
+    if a == 0 { 64 } else { 63 - clz64(a & a.wrapping_neg()) as i32 }
+}
+
+#[no_mangle]
+pub extern "C" fn __ctzti2(a: i128) -> i32 {
+    let a = a as u128;
+
+    if a == 0 { 128 } else { 127 - clz128(a & a.wrapping_neg()) as i32 }
+}
+
+#[no_mangle]
+pub extern "C" fn __popcountsi2(a: i32) -> i32 {
+    let x = a as u32;
+    let x = x - ((x >> 1) & 0x5555_5555);
+    let x = (x & 0x3333_3333) + ((x >> 2) & 0x3333_3333);
+    let x = (x + (x >> 4)) & 0x0f0f_0f0f;
+
+    (x.wrapping_mul(0x0101_0101) >> 24) as i32
+}
+
+#[no_mangle]
+pub extern "C" fn __popcountdi2(a: i64) -> i32 {
+    __popcountsi2(a as i32) + __popcountsi2((a as u64 >> 32) as i32)
+}
+
+#[no_mangle]
+pub extern "C" fn __popcountti2(a: i128) -> i32 {
+    __popcountdi2(a as i64) + __popcountdi2((a as u128 >> 64) as i64)
+}
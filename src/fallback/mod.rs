@@ -0,0 +1,91 @@
+//! Rust implementations of the core integer and soft-float intrinsics
+//!
+//! These are used instead of the compiler-rt archive when there's no C toolchain for the target
+//! (or when the `rust-fallback` feature is enabled).
+//!
+//! NOTE These functions must not use operations that LLVM lowers to calls to the very intrinsics
+//! they implement. Hence: no `/` or `%`, no 64-bit `*` (`__aeabi_lmul` on ARMv6-M), no
+//! `leading_zeros` (`__clzsi2` on targets without a CLZ instruction) and no floating point
+//! arithmetic. Additions, subtractions, shifts and comparisons of any width are fine.
+
+mod float;
+mod int;
+
+#[cfg(target_arch = "arm")]
+mod aeabi;
+
+/// Number of leading zeros of `x`
+fn clz32(mut x: u32) -> u32 {
+    if x == 0 {
+        return 32;
+    }
+
+    let mut n = 0;
+    if x & 0xffff_0000 == 0 {
+        n += 16;
+        x <<= 16;
+    }
+    if x & 0xff00_0000 == 0 {
+        n += 8;
+        x <<= 8;
+    }
+    if x & 0xf000_0000 == 0 {
+        n += 4;
+        x <<= 4;
+    }
+    if x & 0xc000_0000 == 0 {
+        n += 2;
+        x <<= 2;
+    }
+    if x & 0x8000_0000 == 0 {
+        n += 1;
+    }
+    n
+}
+
+fn clz64(x: u64) -> u32 {
+    let hi = (x >> 32) as u32;
+
+    if hi == 0 { 32 + clz32(x as u32) } else { clz32(hi) }
+}
+
+fn clz128(x: u128) -> u32 {
+    let hi = (x >> 64) as u64;
+
+    if hi == 0 { 64 + clz64(x as u64) } else { clz64(hi) }
+}
+
+/// Full 64-bit product of `a` and `b`, using only 32-bit multiplications
+fn wmul32(a: u32, b: u32) -> u64 {
+    let (a_lo, a_hi) = (a & 0xffff, a >> 16);
+    let (b_lo, b_hi) = (b & 0xffff, b >> 16);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    // NOTE none of these can overflow
+    let mid = (lo_lo >> 16) + (hi_lo & 0xffff) + (lo_hi & 0xffff);
+    let lo = (lo_lo & 0xffff) | (mid << 16);
+    let hi = hi_hi + (hi_lo >> 16) + (lo_hi >> 16) + (mid >> 16);
+
+    (hi as u64) << 32 | lo as u64
+}
+
+/// Full 128-bit product of `a` and `b`, as `(high, low)` halves
+fn wmul64(a: u64, b: u64) -> (u64, u64) {
+    let (a_lo, a_hi) = (a as u32, (a >> 32) as u32);
+    let (b_lo, b_hi) = (b as u32, (b >> 32) as u32);
+
+    let lo_lo = wmul32(a_lo, b_lo);
+    let hi_lo = wmul32(a_hi, b_lo);
+    let lo_hi = wmul32(a_lo, b_hi);
+    let hi_hi = wmul32(a_hi, b_hi);
+
+    let mid = (lo_lo >> 32) + (hi_lo & 0xffff_ffff) + (lo_hi & 0xffff_ffff);
+    let lo = (lo_lo & 0xffff_ffff) | (mid << 32);
+    let hi = hi_hi + (hi_lo >> 32) + (lo_hi >> 32) + (mid >> 32);
+
+    (hi, lo)
+}
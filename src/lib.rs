@@ -1,2 +1,31 @@
-#![feature(no_core)]
-#![no_core]
+//! Declarations of the compiler-rt intrinsics that were built for the target
+//!
+//! Only the intrinsics that ended up in `libcompiler-rt.a` are declared, so what's available
+//! depends on the target. For example, `aeabi` only exists when compiling for ARM and
+//! `integer::__divti3` only exists on targets with 128-bit integers.
+//!
+//! The crate is `no_builtins` so LLVM doesn't turn its code into calls to the intrinsics (e.g. a
+//! loop into `memcpy`) and so it's kept out of LTO, which could otherwise internalize or drop the
+//! intrinsics. With the `compiler-builtins` feature the crate is also marked as the builtins crate,
+//! which `rustc` always links last, after every crate that may need the intrinsics.
+
+#![cfg_attr(feature = "compiler-builtins", compiler_builtins)]
+#![cfg_attr(feature = "compiler-builtins", feature(compiler_builtins))]
+#![no_builtins]
+#![no_std]
+
+/// `_Complex float` / `_Complex double`, on the targets that pass and return them like this struct
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+pub use safe::*;
+
+#[cfg(fallback)]
+mod fallback;
+mod safe;
+
+include!(concat!(env!("OUT_DIR"), "/intrinsics.rs"));
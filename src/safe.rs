@@ -0,0 +1,122 @@
+//! Safe wrappers around some of the intrinsics
+//!
+//! Like the declarations, each wrapper only exists if the intrinsic it calls was built for the
+//! target.
+
+/// Multiplies `a` by `b`, returning `None` on overflow (`__mulosi4`)
+#[cfg(intrinsic = "__mulosi4")]
+pub fn checked_mul_i32(a: i32, b: i32) -> Option<i32> {
+    let mut overflow = 0;
+    let product = unsafe { ::integer::__mulosi4(a, b, &mut overflow) };
+
+    if overflow == 0 { Some(product) } else { None }
+}
+
+/// Multiplies `a` by `b`, returning `None` on overflow (`__mulodi4`)
+#[cfg(intrinsic = "__mulodi4")]
+pub fn checked_mul_i64(a: i64, b: i64) -> Option<i64> {
+    let mut overflow = 0;
+    let product = unsafe { ::integer::__mulodi4(a, b, &mut overflow) };
+
+    if overflow == 0 { Some(product) } else { None }
+}
+
+/// Multiplies `a` by `b`, returning `None` on overflow (`__muloti4`)
+#[cfg(intrinsic = "__muloti4")]
+pub fn checked_mul_i128(a: i128, b: i128) -> Option<i128> {
+    let mut overflow = 0;
+    let product = unsafe { ::integer::__muloti4(a, b, &mut overflow) };
+
+    if overflow == 0 { Some(product) } else { None }
+}
+
+// NOTE the intrinsics are only called with values whose truncated value fits in the integer type.
+// Like a C cast, they don't define the result of the others: the soft-float implementations
+// saturate but the ones compiled for targets with hardware floating point don't.
+macro_rules! saturating {
+    ($($(#[$attr:meta])* fn $name:ident($float:ident) -> $int:ident = $intrinsic:ident;)+) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(a: $float) -> $int {
+                // NOTE both bounds are exact, or round to the power of two right above `MAX`
+                if a.is_nan() {
+                    0
+                } else if a <= $int::MIN as $float {
+                    $int::MIN
+                } else if a >= $int::MAX as $float {
+                    $int::MAX
+                } else {
+                    unsafe { ::conversion::$intrinsic(a) }
+                }
+            }
+        )+
+    }
+}
+
+saturating! {
+    /// Converts `a` to `i32`, saturating at the bounds of `i32`. NaN converts to `0` (`__fixdfsi`)
+    #[cfg(intrinsic = "__fixdfsi")]
+    fn f64_to_i32_sat(f64) -> i32 = __fixdfsi;
+    /// Converts `a` to `i64`, saturating at the bounds of `i64`. NaN converts to `0` (`__fixdfdi`)
+    #[cfg(intrinsic = "__fixdfdi")]
+    fn f64_to_i64_sat(f64) -> i64 = __fixdfdi;
+    /// Converts `a` to `u32`, saturating at the bounds of `u32`. NaN converts to `0`
+    /// (`__fixunsdfsi`)
+    #[cfg(intrinsic = "__fixunsdfsi")]
+    fn f64_to_u32_sat(f64) -> u32 = __fixunsdfsi;
+    /// Converts `a` to `u64`, saturating at the bounds of `u64`. NaN converts to `0`
+    /// (`__fixunsdfdi`)
+    #[cfg(intrinsic = "__fixunsdfdi")]
+    fn f64_to_u64_sat(f64) -> u64 = __fixunsdfdi;
+    /// Converts `a` to `i32`, saturating at the bounds of `i32`. NaN converts to `0` (`__fixsfsi`)
+    #[cfg(intrinsic = "__fixsfsi")]
+    fn f32_to_i32_sat(f32) -> i32 = __fixsfsi;
+    /// Converts `a` to `i64`, saturating at the bounds of `i64`. NaN converts to `0` (`__fixsfdi`)
+    #[cfg(intrinsic = "__fixsfdi")]
+    fn f32_to_i64_sat(f32) -> i64 = __fixsfdi;
+    /// Converts `a` to `u32`, saturating at the bounds of `u32`. NaN converts to `0`
+    /// (`__fixunssfsi`)
+    #[cfg(intrinsic = "__fixunssfsi")]
+    fn f32_to_u32_sat(f32) -> u32 = __fixunssfsi;
+    /// Converts `a` to `u64`, saturating at the bounds of `u64`. NaN converts to `0`
+    /// (`__fixunssfdi`)
+    #[cfg(intrinsic = "__fixunssfdi")]
+    fn f32_to_u64_sat(f32) -> u64 = __fixunssfdi;
+}
+
+/// Raises `a` to the integer power `b` (`__powisf2`)
+#[cfg(intrinsic = "__powisf2")]
+pub fn powi_f32(a: f32, b: i32) -> f32 {
+    unsafe { ::float::__powisf2(a, b) }
+}
+
+/// Raises `a` to the integer power `b` (`__powidf2`)
+#[cfg(intrinsic = "__powidf2")]
+pub fn powi_f64(a: f64, b: i32) -> f64 {
+    unsafe { ::float::__powidf2(a, b) }
+}
+
+/// Multiplies two complex numbers with C99 Annex G semantics, e.g. an infinite operand gives an
+/// infinite result even if the naive formula gives NaN (`__mulsc3`)
+#[cfg(intrinsic = "__mulsc3")]
+pub fn mul_c32(a: ::Complex<f32>, b: ::Complex<f32>) -> ::Complex<f32> {
+    unsafe { ::complex::__mulsc3(a.re, a.im, b.re, b.im) }
+}
+
+/// Multiplies two complex numbers with C99 Annex G semantics (`__muldc3`)
+#[cfg(intrinsic = "__muldc3")]
+pub fn mul_c64(a: ::Complex<f64>, b: ::Complex<f64>) -> ::Complex<f64> {
+    unsafe { ::complex::__muldc3(a.re, a.im, b.re, b.im) }
+}
+
+/// Divides `a` by `b` with C99 Annex G semantics (`__divsc3`)
+#[cfg(intrinsic = "__divsc3")]
+pub fn div_c32(a: ::Complex<f32>, b: ::Complex<f32>) -> ::Complex<f32> {
+    unsafe { ::complex::__divsc3(a.re, a.im, b.re, b.im) }
+}
+
+/// Divides `a` by `b` with C99 Annex G semantics (`__divdc3`)
+#[cfg(intrinsic = "__divdc3")]
+pub fn div_c64(a: ::Complex<f64>, b: ::Complex<f64>) -> ::Complex<f64> {
+    unsafe { ::complex::__divdc3(a.re, a.im, b.re, b.im) }
+}
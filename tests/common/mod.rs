@@ -0,0 +1,43 @@
+//! Code shared by the test suites
+
+#![allow(dead_code)]
+
+use std::env;
+
+/// xorshift64* generator of test inputs
+pub struct Rng {
+    /// Reported on failures so they can be reproduced
+    pub seed: u64,
+    state: u64,
+}
+
+impl Rng {
+    /// Seeds the generator from the `var` variable, if set
+    pub fn new(var: &str) -> Rng {
+        let seed = env::var(var)
+            .ok()
+            .map(|seed| seed.parse().expect(&format!("{} is not a number", var)))
+            .unwrap_or(0x2545_f491_4f6c_dd1d);
+
+        Rng {
+            seed: seed,
+            state: if seed == 0 { 1 } else { seed },
+        }
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    pub fn next_u128(&mut self) -> u128 {
+        (self.next() as u128) << 64 | self.next() as u128
+    }
+
+    /// Shift amount for a type of `bits` bits
+    pub fn shift(&mut self, bits: u32) -> u32 {
+        (self.next() % bits as u64) as u32
+    }
+}
@@ -0,0 +1,1196 @@
+//! IEEE 754 conformance tests of the soft-float intrinsics
+//!
+//! The operands are generated the way TestFloat does it: sign, exponent and significand are picked
+//! separately, favoring the boundaries of the format (zeros, subnormals, the smallest and largest
+//! normals, infinities, NaNs, and significands that are all ones, all zeros or a single bit).
+//! Results must match bit for bit, so rounding, subnormals and signed zeros are checked exactly;
+//! a NaN result only has to be a NaN, but when an operand is a NaN its payload must be propagated.
+//!
+//! The expected results come from the host's hardware floating point where that's IEEE compliant
+//! (SSE on x86_64) and from the integer-only reference implementations below for the operations
+//! that Rust lowers to calls to the intrinsics under test (half precision, 128-bit integers).
+//!
+//! Only round to nearest, ties to even is tested, which is the only rounding mode compiler-rt
+//! implements. Conversions to integers are only checked for inputs whose truncated value fits in
+//! the integer type; NaNs, infinities and out of range values are undefined behavior in C. The
+//! saturating wrappers, which handle those values without calling the intrinsics, are tested in
+//! `tests/safe.rs`.
+//!
+//! The exhaustive tests, which cover every input of the single precision (and 32-bit integer)
+//! conversions, take a while; run them with `cargo test --release -- --ignored`. To reproduce a
+//! failure of the other tests, set `FLOAT_TEST_SEED` to the seed it reports.
+
+#![allow(dead_code)]
+
+extern crate compiler_rt;
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+#[allow(unused_imports)]
+use compiler_rt::{conversion, float};
+
+use common::Rng;
+
+/// Number of inputs each intrinsic is tested with
+const ITERATIONS: usize = 200_000;
+
+/// Number of mismatches that are reported
+const REPORTED: usize = 16;
+
+/// Variable that seeds the generator of inputs
+const SEED: &'static str = "FLOAT_TEST_SEED";
+
+/// An IEEE 754 binary interchange format
+struct Format {
+    bits: u32,
+    sig_bits: u32,
+}
+
+const HALF: Format = Format {
+    bits: 16,
+    sig_bits: 10,
+};
+
+const SINGLE: Format = Format {
+    bits: 32,
+    sig_bits: 23,
+};
+
+const DOUBLE: Format = Format {
+    bits: 64,
+    sig_bits: 52,
+};
+
+impl Format {
+    fn exp_bits(&self) -> u32 {
+        self.bits - self.sig_bits - 1
+    }
+
+    fn bias(&self) -> i32 {
+        (1 << (self.exp_bits() - 1)) - 1
+    }
+
+    /// Biased exponent of infinities and NaNs
+    fn max_exp(&self) -> u64 {
+        (1 << self.exp_bits()) - 1
+    }
+
+    fn sign(&self) -> u64 {
+        1 << (self.bits - 1)
+    }
+
+    fn sig_mask(&self) -> u64 {
+        (1 << self.sig_bits) - 1
+    }
+
+    fn quiet(&self) -> u64 {
+        1 << (self.sig_bits - 1)
+    }
+
+    fn inf(&self) -> u64 {
+        self.max_exp() << self.sig_bits
+    }
+
+    fn is_nan(&self, x: u64) -> bool {
+        x & !self.sign() > self.inf()
+    }
+
+    fn is_inf(&self, x: u64) -> bool {
+        x & !self.sign() == self.inf()
+    }
+
+    /// TestFloat style operand
+    fn operand(&self, rng: &mut Rng) -> u64 {
+        let max_exp = self.max_exp();
+        let bias = self.bias() as u64;
+
+        let exp = match rng.next() % 8 {
+            // Zeros and subnormals
+            0 => 0,
+            // Infinities and NaNs
+            1 => max_exp,
+            2 => 1,
+            3 => max_exp - 1,
+            // Around 1.0
+            4 => bias + rng.next() % 5 - 2,
+            _ => rng.next() % max_exp,
+        };
+
+        let mask = self.sig_mask();
+        let k = rng.next() % self.sig_bits as u64;
+        let sig = match rng.next() % 7 {
+            0 => 0,
+            1 => mask,
+            2 => 1 << k,
+            // Run of ones at the top / at the bottom
+            3 => (mask << k) & mask,
+            4 => mask >> k,
+            5 => rng.next() & mask ^ 1 << k,
+            _ => rng.next() & mask,
+        };
+
+        (rng.next() & 1) << (self.bits - 1) | exp << self.sig_bits | sig
+    }
+
+    /// Operand close to `x`, to exercise cancellation and ties
+    fn nearby(&self, x: u64, rng: &mut Rng) -> u64 {
+        let delta = rng.next() % 5;
+        let x = if rng.next() % 2 == 0 { x.wrapping_add(delta) } else { x.wrapping_sub(delta) };
+        let x = if rng.next() % 2 == 0 { x ^ self.sign() } else { x };
+
+        x & (!0 >> (64 - self.bits))
+    }
+
+    /// Decodes the finite `x` as `(negative, significand, exponent)`; its value is
+    /// `significand * 2^exponent`
+    fn decode(&self, x: u64) -> (bool, u128, i32) {
+        let negative = x & self.sign() != 0;
+        let exp = ((x & !self.sign()) >> self.sig_bits) as i32;
+        let sig = x & self.sig_mask();
+
+        if exp == 0 {
+            (negative, sig as u128, 1 - self.bias() - self.sig_bits as i32)
+        } else {
+            (negative,
+             (sig | 1 << self.sig_bits) as u128,
+             exp - self.bias() - self.sig_bits as i32)
+        }
+    }
+
+    /// Rounds `significand * 2^exp` to this format, to nearest with ties to even
+    fn round(&self, negative: bool, sig: u128, exp: i32) -> u64 {
+        let sign = if negative { self.sign() } else { 0 };
+        if sig == 0 {
+            return sign;
+        }
+
+        let sig_bits = self.sig_bits as i32;
+        let bias = self.bias();
+
+        // Exponent of the leading bit and of the last bit that's kept
+        let leading = 127 - sig.leading_zeros() as i32 + exp;
+        let last = if leading - sig_bits > 1 - bias - sig_bits {
+            leading - sig_bits
+        } else {
+            1 - bias - sig_bits
+        };
+
+        let shift = last - exp;
+        let (mut m, up) = if shift <= 0 {
+            (sig << -shift, false)
+        } else if shift > 128 {
+            (0, false)
+        } else {
+            let m = if shift == 128 { 0 } else { sig >> shift };
+            let rem = if shift == 128 { sig } else { sig & ((1 << shift) - 1) };
+            let half = 1 << (shift - 1);
+
+            (m, rem > half || (rem == half && m & 1 == 1))
+        };
+
+        let mut biased = last + sig_bits + bias;
+        if up {
+            m += 1;
+            if m == 1 << (sig_bits + 1) {
+                m >>= 1;
+                biased += 1;
+            }
+        }
+
+        if biased as u64 >= self.max_exp() {
+            sign | self.inf()
+        } else if m < 1 << sig_bits {
+            // Subnormal
+            sign | m as u64
+        } else {
+            sign | (biased as u64) << sig_bits | (m as u64 & self.sig_mask())
+        }
+    }
+
+    /// Converts `x` to the format `to`
+    fn convert(&self, x: u64, to: &Format) -> u64 {
+        let sign = if x & self.sign() != 0 { to.sign() } else { 0 };
+
+        if self.is_nan(x) {
+            // The payload keeps its most significant bits. NOTE unlike IEEE 754 (and the hardware)
+            // compiler-rt only quiets signaling NaNs when narrowing, where the truncated payload
+            // could otherwise be zero
+            let payload = x & self.sig_mask();
+            if to.sig_bits > self.sig_bits {
+                sign | to.inf() | payload << (to.sig_bits - self.sig_bits)
+            } else {
+                sign | to.inf() | to.quiet() | payload >> (self.sig_bits - to.sig_bits)
+            }
+        } else if self.is_inf(x) {
+            sign | to.inf()
+        } else {
+            let (negative, sig, exp) = self.decode(x);
+            to.round(negative, sig, exp)
+        }
+    }
+}
+
+/// A floating point type, as seen by the intrinsics
+trait Float: Copy {
+    fn format() -> &'static Format;
+    fn repr(self) -> u64;
+    fn from_repr(x: u64) -> Self;
+}
+
+impl Float for f32 {
+    fn format() -> &'static Format {
+        &SINGLE
+    }
+
+    fn repr(self) -> u64 {
+        self.to_bits() as u64
+    }
+
+    fn from_repr(x: u64) -> f32 {
+        f32::from_bits(x as u32)
+    }
+}
+
+impl Float for f64 {
+    fn format() -> &'static Format {
+        &DOUBLE
+    }
+
+    fn repr(self) -> u64 {
+        self.to_bits()
+    }
+
+    fn from_repr(x: u64) -> f64 {
+        f64::from_bits(x)
+    }
+}
+
+/// Half precision values are passed around as their representation
+impl Float for u16 {
+    fn format() -> &'static Format {
+        &HALF
+    }
+
+    fn repr(self) -> u64 {
+        self as u64
+    }
+
+    fn from_repr(x: u64) -> u16 {
+        x as u16
+    }
+}
+
+/// Mismatches found while testing an intrinsic
+struct Report {
+    name: &'static str,
+    mismatches: Vec<String>,
+    total: usize,
+}
+
+impl Report {
+    fn new(name: &'static str) -> Report {
+        Report {
+            name: name,
+            mismatches: vec![],
+            total: 0,
+        }
+    }
+
+    fn add(&mut self, mismatch: String) {
+        self.total += 1;
+        if self.mismatches.len() < REPORTED {
+            self.mismatches.push(mismatch);
+        }
+    }
+
+    fn finish(mut self, seed: Option<u64>) {
+        if self.total == 0 {
+            return;
+        }
+
+        self.mismatches.sort();
+        let seed = seed.map(|seed| format!(" (seed: {})", seed)).unwrap_or_default();
+        panic!("{}: {} mismatches{}\n{}",
+               self.name,
+               self.total,
+               seed,
+               self.mismatches.join("\n"));
+    }
+}
+
+/// Runs `case` on `ITERATIONS` random inputs. `case` returns a description of the mismatch, if
+/// any
+fn random<C>(name: &'static str, case: C)
+    where C: Fn(&mut Rng) -> Option<String>
+{
+    let mut rng = Rng::new(SEED);
+    let mut report = Report::new(name);
+
+    for _ in 0..ITERATIONS {
+        if let Some(mismatch) = case(&mut rng) {
+            report.add(mismatch);
+        }
+    }
+
+    report.finish(Some(rng.seed))
+}
+
+/// Runs `case` on every 32-bit input, using all the cores
+fn exhaustive<C>(name: &'static str, case: C)
+    where C: Fn(u32) -> Option<String> + Sync
+{
+    const CHUNK: usize = 1 << 16;
+
+    let next = AtomicUsize::new(0);
+    let report = Mutex::new(Report::new(name));
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                loop {
+                    let chunk = next.fetch_add(1, Ordering::Relaxed);
+                    if chunk >= (1 << 32) / CHUNK {
+                        break;
+                    }
+
+                    for x in chunk * CHUNK..(chunk + 1) * CHUNK {
+                        if let Some(mismatch) = case(x as u32) {
+                            report.lock().unwrap().add(mismatch);
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    report.into_inner().unwrap().finish(None)
+}
+
+/// Checks the result of an arithmetic operation on `operands` against the `expected` one
+fn same_result(format: &Format, operands: &[u64], result: u64, expected: u64) -> bool {
+    if !format.is_nan(expected) {
+        return result == expected;
+    }
+
+    // NOTE the sign of a NaN is not specified
+    let payload = |x: u64| (x | format.quiet()) & !format.sign();
+    format.is_nan(result) &&
+    (operands.iter().all(|&x| !format.is_nan(x)) ||
+     operands.iter().any(|&x| format.is_nan(x) && payload(x) == payload(result)))
+}
+
+/// Tests the arithmetic `intrinsic` against the `native` operation
+fn arith<F, I, N>(name: &'static str, intrinsic: I, native: N)
+    where F: Float,
+          I: Fn(F, F) -> F,
+          N: Fn(F, F) -> F
+{
+    let format = F::format();
+
+    random(name, |rng| {
+        let a = format.operand(rng);
+        let b = if rng.next() % 8 == 0 { format.nearby(a, rng) } else { format.operand(rng) };
+
+        let result = intrinsic(F::from_repr(a), F::from_repr(b)).repr();
+        let expected = native(F::from_repr(a), F::from_repr(b)).repr();
+
+        if same_result(format, &[a, b], result, expected) {
+            None
+        } else {
+            Some(format!("{}({:#x}, {:#x}) = {:#x}, expected {:#x}", name, a, b, result, expected))
+        }
+    })
+}
+
+/// Tests the negation `intrinsic`, which only flips the sign bit (of NaNs too)
+fn negation<F, I>(name: &'static str, intrinsic: I)
+    where F: Float,
+          I: Fn(F) -> F
+{
+    let format = F::format();
+
+    random(name, |rng| {
+        let a = format.operand(rng);
+        let result = intrinsic(F::from_repr(a)).repr();
+        let expected = a ^ format.sign();
+
+        if result == expected {
+            None
+        } else {
+            Some(format!("{}({:#x}) = {:#x}, expected {:#x}", name, a, result, expected))
+        }
+    })
+}
+
+/// Tests the comparison `intrinsic`; `holds` tells if its result agrees with the native
+/// comparison of the operands
+fn comparison<F, I>(name: &'static str, intrinsic: I, holds: fn(i32, F, F) -> bool)
+    where F: Float,
+          I: Fn(F, F) -> i32
+{
+    let format = F::format();
+
+    random(name, |rng| {
+        let a = format.operand(rng);
+        let b = if rng.next() % 4 == 0 { format.nearby(a, rng) } else { format.operand(rng) };
+        let (fa, fb) = (F::from_repr(a), F::from_repr(b));
+        let result = intrinsic(fa, fb);
+
+        if holds(result, fa, fb) {
+            None
+        } else {
+            Some(format!("{}({:#x}, {:#x}) = {}", name, a, b, result))
+        }
+    })
+}
+
+/// Checks the conversion of `a` to another floating point format
+fn check_conversion<F, G, I>(name: &str, intrinsic: &I, a: u64) -> Option<String>
+    where F: Float,
+          G: Float,
+          I: Fn(F) -> G
+{
+    let result = intrinsic(F::from_repr(a)).repr();
+    let expected = F::format().convert(a, G::format());
+
+    if result == expected {
+        None
+    } else {
+        Some(format!("{}({:#x}) = {:#x}, expected {:#x}", name, a, result, expected))
+    }
+}
+
+/// Checks the conversion of `a` to a `bits`-bit integer. `intrinsic` returns the integer sign
+/// extended to 128 bits
+fn check_to_int<F, I>(name: &str, intrinsic: &I, a: u64, bits: u32, signed: bool) -> Option<String>
+    where F: Float,
+          I: Fn(F) -> u128
+{
+    let format = F::format();
+    if format.is_nan(a) || format.is_inf(a) {
+        return None;
+    }
+
+    // Truncate towards zero
+    let (negative, sig, exp) = format.decode(a);
+    let magnitude = if exp >= 0 {
+        if sig.leading_zeros() < exp as u32 {
+            return None;
+        }
+        sig << exp
+    } else if exp <= -128 {
+        0
+    } else {
+        sig >> -exp
+    };
+
+    let max = if signed {
+        (1 << (bits - 1)) - 1 + negative as u128
+    } else if negative {
+        0
+    } else {
+        !0 >> (128 - bits)
+    };
+    if magnitude > max {
+        return None;
+    }
+
+    let result = intrinsic(F::from_repr(a));
+    let expected = if negative { magnitude.wrapping_neg() } else { magnitude };
+
+    if result == expected {
+        None
+    } else {
+        Some(format!("{}({:#x}) = {:#x}, expected {:#x}", name, a, result, expected))
+    }
+}
+
+/// Checks the conversion of the `bits`-bit integer `a` (sign extended to 128 bits) to a floating
+/// point format
+fn check_from_int<G, I>(name: &str, intrinsic: &I, a: u128, signed: bool) -> Option<String>
+    where G: Float,
+          I: Fn(u128) -> G
+{
+    let negative = signed && (a as i128) < 0;
+    let magnitude = if negative { a.wrapping_neg() } else { a };
+
+    let result = intrinsic(a).repr();
+    let expected = G::format().round(negative, magnitude, 0);
+
+    if result == expected {
+        None
+    } else {
+        Some(format!("{}({:#x}) = {:#x}, expected {:#x}", name, a, result, expected))
+    }
+}
+
+/// Floating point operand for a conversion to a `bits`-bit integer; half of them are around the
+/// limits of the integer type
+fn to_int_operand(format: &Format, bits: u32, rng: &mut Rng) -> u64 {
+    let x = format.operand(rng);
+
+    if rng.next() % 2 == 0 {
+        let exp = format.bias() as u64 + rng.next() % (bits as u64 + 2);
+        x & !(format.max_exp() << format.sig_bits) | exp << format.sig_bits
+    } else {
+        x
+    }
+}
+
+/// `bits`-bit integer operand for a conversion to a floating point format with `sig_bits`
+/// significand bits, sign extended to 128 bits if `signed`
+fn from_int_operand(bits: u32, sig_bits: u32, signed: bool, rng: &mut Rng) -> u128 {
+    let k = rng.shift(bits);
+    let x: u128 = match rng.next() % 6 {
+        0 => [0, 1, !0, 1 << (bits - 1), (1 << (bits - 1)) - 1][(rng.next() % 5) as usize],
+        1 => 1 << k,
+        // Exactly halfway between two floats, or right next to it
+        2 if k > sig_bits => {
+            let halfway: u128 = 1 << k | 1 << (k - sig_bits - 1);
+            halfway.wrapping_add((rng.next() % 3) as u128).wrapping_sub(1)
+        }
+        _ => rng.next_u128() >> (128 - bits) >> k,
+    };
+
+    let x = x & (!0 >> (128 - bits));
+    if signed && x >> (bits - 1) & 1 == 1 {
+        x | !0 << (bits - 1)
+    } else {
+        x
+    }
+}
+
+// Arithmetic
+
+#[cfg(intrinsic = "__addsf3")]
+#[test]
+fn addsf3() {
+    arith("__addsf3",
+          |a, b| unsafe { float::__addsf3(a, b) },
+          |a: f32, b| a + b);
+}
+
+#[cfg(intrinsic = "__subsf3")]
+#[test]
+fn subsf3() {
+    arith("__subsf3",
+          |a, b| unsafe { float::__subsf3(a, b) },
+          |a: f32, b| a - b);
+}
+
+#[cfg(intrinsic = "__mulsf3")]
+#[test]
+fn mulsf3() {
+    arith("__mulsf3",
+          |a, b| unsafe { float::__mulsf3(a, b) },
+          |a: f32, b| a * b);
+}
+
+#[cfg(intrinsic = "__divsf3")]
+#[test]
+fn divsf3() {
+    arith("__divsf3",
+          |a, b| unsafe { float::__divsf3(a, b) },
+          |a: f32, b| a / b);
+}
+
+#[cfg(intrinsic = "__adddf3")]
+#[test]
+fn adddf3() {
+    arith("__adddf3",
+          |a, b| unsafe { float::__adddf3(a, b) },
+          |a: f64, b| a + b);
+}
+
+#[cfg(intrinsic = "__subdf3")]
+#[test]
+fn subdf3() {
+    arith("__subdf3",
+          |a, b| unsafe { float::__subdf3(a, b) },
+          |a: f64, b| a - b);
+}
+
+#[cfg(intrinsic = "__muldf3")]
+#[test]
+fn muldf3() {
+    arith("__muldf3",
+          |a, b| unsafe { float::__muldf3(a, b) },
+          |a: f64, b| a * b);
+}
+
+#[cfg(intrinsic = "__divdf3")]
+#[test]
+fn divdf3() {
+    arith("__divdf3",
+          |a, b| unsafe { float::__divdf3(a, b) },
+          |a: f64, b| a / b);
+}
+
+#[cfg(intrinsic = "__addsf3vfp")]
+#[test]
+fn addsf3vfp() {
+    arith("__addsf3vfp",
+          |a, b| unsafe { float::__addsf3vfp(a, b) },
+          |a: f32, b| a + b);
+}
+
+#[cfg(intrinsic = "__subsf3vfp")]
+#[test]
+fn subsf3vfp() {
+    arith("__subsf3vfp",
+          |a, b| unsafe { float::__subsf3vfp(a, b) },
+          |a: f32, b| a - b);
+}
+
+#[cfg(intrinsic = "__mulsf3vfp")]
+#[test]
+fn mulsf3vfp() {
+    arith("__mulsf3vfp",
+          |a, b| unsafe { float::__mulsf3vfp(a, b) },
+          |a: f32, b| a * b);
+}
+
+#[cfg(intrinsic = "__divsf3vfp")]
+#[test]
+fn divsf3vfp() {
+    arith("__divsf3vfp",
+          |a, b| unsafe { float::__divsf3vfp(a, b) },
+          |a: f32, b| a / b);
+}
+
+#[cfg(intrinsic = "__adddf3vfp")]
+#[test]
+fn adddf3vfp() {
+    arith("__adddf3vfp",
+          |a, b| unsafe { float::__adddf3vfp(a, b) },
+          |a: f64, b| a + b);
+}
+
+#[cfg(intrinsic = "__subdf3vfp")]
+#[test]
+fn subdf3vfp() {
+    arith("__subdf3vfp",
+          |a, b| unsafe { float::__subdf3vfp(a, b) },
+          |a: f64, b| a - b);
+}
+
+#[cfg(intrinsic = "__muldf3vfp")]
+#[test]
+fn muldf3vfp() {
+    arith("__muldf3vfp",
+          |a, b| unsafe { float::__muldf3vfp(a, b) },
+          |a: f64, b| a * b);
+}
+
+#[cfg(intrinsic = "__divdf3vfp")]
+#[test]
+fn divdf3vfp() {
+    arith("__divdf3vfp",
+          |a, b| unsafe { float::__divdf3vfp(a, b) },
+          |a: f64, b| a / b);
+}
+
+#[cfg(intrinsic = "__negsf2")]
+#[test]
+fn negsf2() {
+    negation("__negsf2", |a: f32| unsafe { float::__negsf2(a) });
+}
+
+#[cfg(intrinsic = "__negdf2")]
+#[test]
+fn negdf2() {
+    negation("__negdf2", |a: f64| unsafe { float::__negdf2(a) });
+}
+
+#[cfg(intrinsic = "__negsf2vfp")]
+#[test]
+fn negsf2vfp() {
+    negation("__negsf2vfp", |a: f32| unsafe { float::__negsf2vfp(a) });
+}
+
+#[cfg(intrinsic = "__negdf2vfp")]
+#[test]
+fn negdf2vfp() {
+    negation("__negdf2vfp", |a: f64| unsafe { float::__negdf2vfp(a) });
+}
+
+// Comparisons
+//
+// NOTE `__le*`, `__lt*`, `__eq*` and `__ne*` return 1 for unordered operands and `__ge*` and
+// `__gt*` return -1, so that the comparison of their result with 0 is false. The `*vfp` variants
+// return 1 if the relation holds and 0 otherwise
+
+#[cfg(intrinsic = "__eqsf2")]
+#[test]
+fn eqsf2() {
+    comparison("__eqsf2",
+               |a, b| unsafe { float::__eqsf2(a, b) },
+               |r, a: f32, b| (r == 0) == (a == b));
+}
+
+#[cfg(intrinsic = "__nesf2")]
+#[test]
+fn nesf2() {
+    comparison("__nesf2",
+               |a, b| unsafe { float::__nesf2(a, b) },
+               |r, a: f32, b| (r != 0) == (a != b));
+}
+
+#[cfg(intrinsic = "__ltsf2")]
+#[test]
+fn ltsf2() {
+    comparison("__ltsf2",
+               |a, b| unsafe { float::__ltsf2(a, b) },
+               |r, a: f32, b| (r < 0) == (a < b));
+}
+
+#[cfg(intrinsic = "__lesf2")]
+#[test]
+fn lesf2() {
+    comparison("__lesf2",
+               |a, b| unsafe { float::__lesf2(a, b) },
+               |r, a: f32, b| (r <= 0) == (a <= b));
+}
+
+#[cfg(intrinsic = "__gtsf2")]
+#[test]
+fn gtsf2() {
+    comparison("__gtsf2",
+               |a, b| unsafe { float::__gtsf2(a, b) },
+               |r, a: f32, b| (r > 0) == (a > b));
+}
+
+#[cfg(intrinsic = "__gesf2")]
+#[test]
+fn gesf2() {
+    comparison("__gesf2",
+               |a, b| unsafe { float::__gesf2(a, b) },
+               |r, a: f32, b| (r >= 0) == (a >= b));
+}
+
+#[cfg(intrinsic = "__unordsf2")]
+#[test]
+fn unordsf2() {
+    comparison("__unordsf2",
+               |a, b| unsafe { float::__unordsf2(a, b) },
+               |r, a: f32, b| (r != 0) == (a.is_nan() || b.is_nan()));
+}
+
+#[cfg(intrinsic = "__eqdf2")]
+#[test]
+fn eqdf2() {
+    comparison("__eqdf2",
+               |a, b| unsafe { float::__eqdf2(a, b) },
+               |r, a: f64, b| (r == 0) == (a == b));
+}
+
+#[cfg(intrinsic = "__nedf2")]
+#[test]
+fn nedf2() {
+    comparison("__nedf2",
+               |a, b| unsafe { float::__nedf2(a, b) },
+               |r, a: f64, b| (r != 0) == (a != b));
+}
+
+#[cfg(intrinsic = "__ltdf2")]
+#[test]
+fn ltdf2() {
+    comparison("__ltdf2",
+               |a, b| unsafe { float::__ltdf2(a, b) },
+               |r, a: f64, b| (r < 0) == (a < b));
+}
+
+#[cfg(intrinsic = "__ledf2")]
+#[test]
+fn ledf2() {
+    comparison("__ledf2",
+               |a, b| unsafe { float::__ledf2(a, b) },
+               |r, a: f64, b| (r <= 0) == (a <= b));
+}
+
+#[cfg(intrinsic = "__gtdf2")]
+#[test]
+fn gtdf2() {
+    comparison("__gtdf2",
+               |a, b| unsafe { float::__gtdf2(a, b) },
+               |r, a: f64, b| (r > 0) == (a > b));
+}
+
+#[cfg(intrinsic = "__gedf2")]
+#[test]
+fn gedf2() {
+    comparison("__gedf2",
+               |a, b| unsafe { float::__gedf2(a, b) },
+               |r, a: f64, b| (r >= 0) == (a >= b));
+}
+
+#[cfg(intrinsic = "__unorddf2")]
+#[test]
+fn unorddf2() {
+    comparison("__unorddf2",
+               |a, b| unsafe { float::__unorddf2(a, b) },
+               |r, a: f64, b| (r != 0) == (a.is_nan() || b.is_nan()));
+}
+
+#[cfg(intrinsic = "__eqsf2vfp")]
+#[test]
+fn eqsf2vfp() {
+    comparison("__eqsf2vfp",
+               |a, b| unsafe { float::__eqsf2vfp(a, b) },
+               |r, a: f32, b| (r != 0) == (a == b));
+}
+
+#[cfg(intrinsic = "__nesf2vfp")]
+#[test]
+fn nesf2vfp() {
+    comparison("__nesf2vfp",
+               |a, b| unsafe { float::__nesf2vfp(a, b) },
+               |r, a: f32, b| (r != 0) == (a != b));
+}
+
+#[cfg(intrinsic = "__ltsf2vfp")]
+#[test]
+fn ltsf2vfp() {
+    comparison("__ltsf2vfp",
+               |a, b| unsafe { float::__ltsf2vfp(a, b) },
+               |r, a: f32, b| (r != 0) == (a < b));
+}
+
+#[cfg(intrinsic = "__lesf2vfp")]
+#[test]
+fn lesf2vfp() {
+    comparison("__lesf2vfp",
+               |a, b| unsafe { float::__lesf2vfp(a, b) },
+               |r, a: f32, b| (r != 0) == (a <= b));
+}
+
+#[cfg(intrinsic = "__gtsf2vfp")]
+#[test]
+fn gtsf2vfp() {
+    comparison("__gtsf2vfp",
+               |a, b| unsafe { float::__gtsf2vfp(a, b) },
+               |r, a: f32, b| (r != 0) == (a > b));
+}
+
+#[cfg(intrinsic = "__gesf2vfp")]
+#[test]
+fn gesf2vfp() {
+    comparison("__gesf2vfp",
+               |a, b| unsafe { float::__gesf2vfp(a, b) },
+               |r, a: f32, b| (r != 0) == (a >= b));
+}
+
+#[cfg(intrinsic = "__unordsf2vfp")]
+#[test]
+fn unordsf2vfp() {
+    comparison("__unordsf2vfp",
+               |a, b| unsafe { float::__unordsf2vfp(a, b) },
+               |r, a: f32, b| (r != 0) == (a.is_nan() || b.is_nan()));
+}
+
+#[cfg(intrinsic = "__eqdf2vfp")]
+#[test]
+fn eqdf2vfp() {
+    comparison("__eqdf2vfp",
+               |a, b| unsafe { float::__eqdf2vfp(a, b) },
+               |r, a: f64, b| (r != 0) == (a == b));
+}
+
+#[cfg(intrinsic = "__nedf2vfp")]
+#[test]
+fn nedf2vfp() {
+    comparison("__nedf2vfp",
+               |a, b| unsafe { float::__nedf2vfp(a, b) },
+               |r, a: f64, b| (r != 0) == (a != b));
+}
+
+#[cfg(intrinsic = "__ltdf2vfp")]
+#[test]
+fn ltdf2vfp() {
+    comparison("__ltdf2vfp",
+               |a, b| unsafe { float::__ltdf2vfp(a, b) },
+               |r, a: f64, b| (r != 0) == (a < b));
+}
+
+#[cfg(intrinsic = "__ledf2vfp")]
+#[test]
+fn ledf2vfp() {
+    comparison("__ledf2vfp",
+               |a, b| unsafe { float::__ledf2vfp(a, b) },
+               |r, a: f64, b| (r != 0) == (a <= b));
+}
+
+#[cfg(intrinsic = "__gtdf2vfp")]
+#[test]
+fn gtdf2vfp() {
+    comparison("__gtdf2vfp",
+               |a, b| unsafe { float::__gtdf2vfp(a, b) },
+               |r, a: f64, b| (r != 0) == (a > b));
+}
+
+#[cfg(intrinsic = "__gedf2vfp")]
+#[test]
+fn gedf2vfp() {
+    comparison("__gedf2vfp",
+               |a, b| unsafe { float::__gedf2vfp(a, b) },
+               |r, a: f64, b| (r != 0) == (a >= b));
+}
+
+#[cfg(intrinsic = "__unorddf2vfp")]
+#[test]
+fn unorddf2vfp() {
+    comparison("__unorddf2vfp",
+               |a, b| unsafe { float::__unorddf2vfp(a, b) },
+               |r, a: f64, b| (r != 0) == (a.is_nan() || b.is_nan()));
+}
+
+// Conversions between floating point formats
+
+#[cfg(intrinsic = "__extendsfdf2")]
+#[test]
+fn extendsfdf2() {
+    let intrinsic = |a: f32| unsafe { conversion::__extendsfdf2(a) };
+
+    random("__extendsfdf2",
+           |rng| check_conversion("__extendsfdf2", &intrinsic, SINGLE.operand(rng)));
+}
+
+#[cfg(intrinsic = "__extendsfdf2")]
+#[test]
+#[ignore]
+fn extendsfdf2_exhaustive() {
+    let intrinsic = |a: f32| unsafe { conversion::__extendsfdf2(a) };
+
+    exhaustive("__extendsfdf2",
+               |a| check_conversion("__extendsfdf2", &intrinsic, a as u64));
+}
+
+#[cfg(intrinsic = "__extendsfdf2vfp")]
+#[test]
+fn extendsfdf2vfp() {
+    let intrinsic = |a: f32| unsafe { conversion::__extendsfdf2vfp(a) };
+
+    random("__extendsfdf2vfp",
+           |rng| check_conversion("__extendsfdf2vfp", &intrinsic, SINGLE.operand(rng)));
+}
+
+#[cfg(intrinsic = "__truncdfsf2")]
+#[test]
+fn truncdfsf2() {
+    let intrinsic = |a: f64| unsafe { conversion::__truncdfsf2(a) };
+
+    random("__truncdfsf2",
+           |rng| check_conversion("__truncdfsf2", &intrinsic, DOUBLE.operand(rng)));
+}
+
+#[cfg(intrinsic = "__truncdfsf2vfp")]
+#[test]
+fn truncdfsf2vfp() {
+    let intrinsic = |a: f64| unsafe { conversion::__truncdfsf2vfp(a) };
+
+    random("__truncdfsf2vfp",
+           |rng| check_conversion("__truncdfsf2vfp", &intrinsic, DOUBLE.operand(rng)));
+}
+
+// NOTE there are only 2^16 half precision values
+#[cfg(intrinsic = "__extendhfsf2")]
+#[test]
+fn extendhfsf2() {
+    let intrinsic = |a: u16| unsafe { conversion::__extendhfsf2(a) };
+    let mut report = Report::new("__extendhfsf2");
+
+    for a in 0..1 << 16 {
+        if let Some(mismatch) = check_conversion("__extendhfsf2", &intrinsic, a) {
+            report.add(mismatch);
+        }
+    }
+
+    report.finish(None)
+}
+
+#[cfg(intrinsic = "__gnu_h2f_ieee")]
+#[test]
+fn gnu_h2f_ieee() {
+    let intrinsic = |a: u16| unsafe { conversion::__gnu_h2f_ieee(a) };
+    let mut report = Report::new("__gnu_h2f_ieee");
+
+    for a in 0..1 << 16 {
+        if let Some(mismatch) = check_conversion("__gnu_h2f_ieee", &intrinsic, a) {
+            report.add(mismatch);
+        }
+    }
+
+    report.finish(None)
+}
+
+#[cfg(intrinsic = "__truncsfhf2")]
+#[test]
+fn truncsfhf2() {
+    let intrinsic = |a: f32| unsafe { conversion::__truncsfhf2(a) };
+
+    random("__truncsfhf2",
+           |rng| check_conversion("__truncsfhf2", &intrinsic, SINGLE.operand(rng)));
+}
+
+#[cfg(intrinsic = "__truncsfhf2")]
+#[test]
+#[ignore]
+fn truncsfhf2_exhaustive() {
+    let intrinsic = |a: f32| unsafe { conversion::__truncsfhf2(a) };
+
+    exhaustive("__truncsfhf2",
+               |a| check_conversion("__truncsfhf2", &intrinsic, a as u64));
+}
+
+#[cfg(intrinsic = "__gnu_f2h_ieee")]
+#[test]
+fn gnu_f2h_ieee() {
+    let intrinsic = |a: f32| unsafe { conversion::__gnu_f2h_ieee(a) };
+
+    random("__gnu_f2h_ieee",
+           |rng| check_conversion("__gnu_f2h_ieee", &intrinsic, SINGLE.operand(rng)));
+}
+
+#[cfg(intrinsic = "__truncdfhf2")]
+#[test]
+fn truncdfhf2() {
+    let intrinsic = |a: f64| unsafe { conversion::__truncdfhf2(a) };
+
+    random("__truncdfhf2",
+           |rng| check_conversion("__truncdfhf2", &intrinsic, DOUBLE.operand(rng)));
+}
+
+// Conversions to integers
+
+macro_rules! to_int {
+    ($($cfg:tt, $test:ident: $intrinsic:ident($F:ident), $bits:expr, $signed:expr;)+) => {
+        $(
+            #[cfg(intrinsic = $cfg)]
+            #[test]
+            fn $test() {
+                let intrinsic = |a: $F| unsafe { conversion::$intrinsic(a) } as i128 as u128;
+                let format = <$F as Float>::format();
+
+                random($cfg, |rng| {
+                    let a = to_int_operand(format, $bits, rng);
+                    check_to_int($cfg, &intrinsic, a, $bits, $signed)
+                });
+            }
+        )+
+    }
+}
+
+to_int! {
+    "__fixsfsi", fixsfsi: __fixsfsi(f32), 32, true;
+    "__fixsfdi", fixsfdi: __fixsfdi(f32), 64, true;
+    "__fixsfti", fixsfti: __fixsfti(f32), 128, true;
+    "__fixunssfsi", fixunssfsi: __fixunssfsi(f32), 32, false;
+    "__fixunssfdi", fixunssfdi: __fixunssfdi(f32), 64, false;
+    "__fixunssfti", fixunssfti: __fixunssfti(f32), 128, false;
+    "__fixsfsivfp", fixsfsivfp: __fixsfsivfp(f32), 32, true;
+    "__fixunssfsivfp", fixunssfsivfp: __fixunssfsivfp(f32), 32, false;
+    "__fixdfsi", fixdfsi: __fixdfsi(f64), 32, true;
+    "__fixdfdi", fixdfdi: __fixdfdi(f64), 64, true;
+    "__fixdfti", fixdfti: __fixdfti(f64), 128, true;
+    "__fixunsdfsi", fixunsdfsi: __fixunsdfsi(f64), 32, false;
+    "__fixunsdfdi", fixunsdfdi: __fixunsdfdi(f64), 64, false;
+    "__fixunsdfti", fixunsdfti: __fixunsdfti(f64), 128, false;
+    "__fixdfsivfp", fixdfsivfp: __fixdfsivfp(f64), 32, true;
+    "__fixunsdfsivfp", fixunsdfsivfp: __fixunsdfsivfp(f64), 32, false;
+}
+
+/// Every single precision input
+macro_rules! to_int_exhaustive {
+    ($($cfg:tt, $test:ident: $intrinsic:ident, $bits:expr, $signed:expr;)+) => {
+        $(
+            #[cfg(intrinsic = $cfg)]
+            #[test]
+            #[ignore]
+            fn $test() {
+                let intrinsic = |a: f32| unsafe { conversion::$intrinsic(a) } as i128 as u128;
+
+                exhaustive($cfg, |a| check_to_int($cfg, &intrinsic, a as u64, $bits, $signed));
+            }
+        )+
+    }
+}
+
+to_int_exhaustive! {
+    "__fixsfsi", fixsfsi_exhaustive: __fixsfsi, 32, true;
+    "__fixsfdi", fixsfdi_exhaustive: __fixsfdi, 64, true;
+    "__fixsfti", fixsfti_exhaustive: __fixsfti, 128, true;
+    "__fixunssfsi", fixunssfsi_exhaustive: __fixunssfsi, 32, false;
+    "__fixunssfdi", fixunssfdi_exhaustive: __fixunssfdi, 64, false;
+    "__fixunssfti", fixunssfti_exhaustive: __fixunssfti, 128, false;
+}
+
+// Conversions from integers
+
+macro_rules! from_int {
+    ($($cfg:tt, $test:ident: $intrinsic:ident($ity:ident) -> $F:ident, $bits:expr,
+       $signed:expr;)+) => {
+        $(
+            #[cfg(intrinsic = $cfg)]
+            #[test]
+            fn $test() {
+                let intrinsic = |a: u128| unsafe { conversion::$intrinsic(a as $ity) };
+                let sig_bits = <$F as Float>::format().sig_bits;
+
+                random($cfg, |rng| {
+                    let a = from_int_operand($bits, sig_bits, $signed, rng);
+                    check_from_int($cfg, &intrinsic, a, $signed)
+                });
+            }
+        )+
+    }
+}
+
+from_int! {
+    "__floatsisf", floatsisf: __floatsisf(i32) -> f32, 32, true;
+    "__floatdisf", floatdisf: __floatdisf(i64) -> f32, 64, true;
+    "__floattisf", floattisf: __floattisf(i128) -> f32, 128, true;
+    "__floatunsisf", floatunsisf: __floatunsisf(u32) -> f32, 32, false;
+    "__floatundisf", floatundisf: __floatundisf(u64) -> f32, 64, false;
+    "__floatuntisf", floatuntisf: __floatuntisf(u128) -> f32, 128, false;
+    "__floatsisfvfp", floatsisfvfp: __floatsisfvfp(i32) -> f32, 32, true;
+    "__floatunssisfvfp", floatunssisfvfp: __floatunssisfvfp(u32) -> f32, 32, false;
+    "__floatsidf", floatsidf: __floatsidf(i32) -> f64, 32, true;
+    "__floatdidf", floatdidf: __floatdidf(i64) -> f64, 64, true;
+    "__floattidf", floattidf: __floattidf(i128) -> f64, 128, true;
+    "__floatunsidf", floatunsidf: __floatunsidf(u32) -> f64, 32, false;
+    "__floatundidf", floatundidf: __floatundidf(u64) -> f64, 64, false;
+    "__floatuntidf", floatuntidf: __floatuntidf(u128) -> f64, 128, false;
+    "__floatsidfvfp", floatsidfvfp: __floatsidfvfp(i32) -> f64, 32, true;
+    "__floatunssidfvfp", floatunssidfvfp: __floatunssidfvfp(u32) -> f64, 32, false;
+}
+
+/// Every 32-bit integer
+macro_rules! from_int_exhaustive {
+    ($($cfg:tt, $test:ident: $intrinsic:ident($ity:ident), $signed:expr;)+) => {
+        $(
+            #[cfg(intrinsic = $cfg)]
+            #[test]
+            #[ignore]
+            fn $test() {
+                let intrinsic = |a: u128| unsafe { conversion::$intrinsic(a as $ity) };
+
+                exhaustive($cfg, |a| {
+                    let a = if $signed { a as i32 as u128 } else { a as u128 };
+                    check_from_int($cfg, &intrinsic, a, $signed)
+                });
+            }
+        )+
+    }
+}
+
+from_int_exhaustive! {
+    "__floatsisf", floatsisf_exhaustive: __floatsisf(i32), true;
+    "__floatunsisf", floatunsisf_exhaustive: __floatunsisf(u32), false;
+    "__floatsidf", floatsidf_exhaustive: __floatsidf(i32), true;
+    "__floatunsidf", floatunsidf_exhaustive: __floatunsidf(u32), false;
+}
@@ -0,0 +1,1056 @@
+//! Differential tests of the integer intrinsics against Rust's native arithmetic
+//!
+//! Each intrinsic is called with edge cases (0, 1, -1, `MIN`, `MAX`, powers of two and their
+//! neighbours) and random values of every magnitude, and its result is compared against the one of
+//! the equivalent Rust operator. Only the intrinsics that were built for the host are tested.
+//!
+//! The inputs come from a xorshift generator. To reproduce a failure, set `INTEGER_TEST_SEED` to
+//! the seed it reports.
+
+#![allow(dead_code)]
+
+extern crate compiler_rt;
+
+mod common;
+
+use std::env;
+use std::fmt::Debug;
+use std::process::Command;
+
+#[allow(unused_imports)]
+use compiler_rt::integer;
+
+use common::Rng;
+
+/// Number of inputs each intrinsic is tested with
+const ITERATIONS: usize = 100_000;
+
+/// Variable that seeds the generator of inputs
+const SEED: &'static str = "INTEGER_TEST_SEED";
+
+/// Variable that tells the `abort_child` test which intrinsic to call
+const ABORT_CALL: &'static str = "INTEGER_TEST_ABORT_CALL";
+
+macro_rules! generators {
+    ($($name:ident: $ty:ident, $uty:ident, $next:ident;)+) => {
+        impl Rng {
+            $(
+                fn $name(&mut self) -> $ty {
+                    const BITS: u32 = 8 * std::mem::size_of::<$uty>() as u32;
+
+                    let k = self.shift(BITS);
+                    let x = if self.next() % 4 == 0 {
+                        // Edge case
+                        let edges: [$uty; 7] = [0,
+                                                1,
+                                                !0,
+                                                1 << (BITS - 1),
+                                                !0 >> 1,
+                                                1 << k,
+                                                (1 << k) - 1];
+                        let x = edges[(self.next() % 7) as usize];
+
+                        if self.next() % 2 == 0 { x } else { x.wrapping_add(1) }
+                    } else {
+                        // Random value of random magnitude
+                        self.$next() as $uty >> k
+                    };
+
+                    (if self.next() % 2 == 0 { x } else { x.wrapping_neg() }) as $ty
+                }
+            )+
+        }
+    }
+}
+
+generators! {
+    i32: i32, u32, next;
+    i64: i64, u64, next;
+    i128: i128, u128, next_u128;
+    u32: u32, u32, next;
+    u64: u64, u64, next;
+    u128: u128, u128, next_u128;
+}
+
+/// Reference implementations of the operations that Rust itself may lower to calls to the
+/// intrinsics under test (e.g. `/` on `i128` becomes a call to `__divti3`, which the linker would
+/// resolve to the archive's). These only use shifts, additions, comparisons and multiplications of
+/// 32-bit limbs
+mod reference {
+    /// Quotient and remainder of `n / d`, by shift and subtract
+    pub fn udivmod(n: u128, d: u128) -> (u128, u128) {
+        let mut q = 0;
+        let mut r = 0u128;
+        for i in (0..128).rev() {
+            let carry = r >> 127;
+            r = r << 1 | (n >> i) & 1;
+
+            if carry != 0 || r >= d {
+                r = r.wrapping_sub(d);
+                q |= 1 << i;
+            }
+        }
+
+        (q, r)
+    }
+
+    /// Truncated quotient and remainder of `a / b`
+    pub fn divmod(a: i128, b: i128) -> (i128, i128) {
+        let ua = if a < 0 { (a as u128).wrapping_neg() } else { a as u128 };
+        let ub = if b < 0 { (b as u128).wrapping_neg() } else { b as u128 };
+        let (q, r) = udivmod(ua, ub);
+
+        let q = if (a < 0) != (b < 0) { q.wrapping_neg() } else { q };
+        let r = if a < 0 { r.wrapping_neg() } else { r };
+        (q as i128, r as i128)
+    }
+
+    /// Full product of `a` and `b`, as `(high, low)` halves
+    pub fn wmul(a: u128, b: u128) -> (u128, u128) {
+        let limbs = |x: u128| [x as u32, (x >> 32) as u32, (x >> 64) as u32, (x >> 96) as u32];
+        let (a, b) = (limbs(a), limbs(b));
+
+        let mut p = [0u32; 8];
+        for i in 0..4 {
+            let mut carry = 0;
+            for j in 0..4 {
+                let t = a[i] as u64 * b[j] as u64 + p[i + j] as u64 + carry;
+                p[i + j] = t as u32;
+                carry = t >> 32;
+            }
+            p[i + 4] = carry as u32;
+        }
+
+        let join = |p: &[u32]| {
+            (p[3] as u128) << 96 | (p[2] as u128) << 64 | (p[1] as u128) << 32 | p[0] as u128
+        };
+        (join(&p[4..]), join(&p[..4]))
+    }
+
+    /// Wrapping product of the `bits`-bit integers `a` and `b` (sign extended to 128 bits) and
+    /// whether it overflowed
+    pub fn mul(a: i128, b: i128, bits: u32) -> (i128, bool) {
+        let ua = if a < 0 { (a as u128).wrapping_neg() } else { a as u128 };
+        let ub = if b < 0 { (b as u128).wrapping_neg() } else { b as u128 };
+        let negative = (a < 0) != (b < 0);
+
+        let (hi, lo) = wmul(ua, ub);
+        let max = (!0u128 >> (129 - bits)) + negative as u128;
+        let product = if negative { lo.wrapping_neg() } else { lo };
+
+        (product as i128, hi != 0 || lo > max)
+    }
+}
+
+/// `(a / b, a % b)`, or `None` if the quotient is undefined or doesn't fit in `bits` bits
+fn checked_divmod(a: i128, b: i128, bits: u32) -> Option<(i128, i128)> {
+    if b == 0 || (b == -1 && a == i128::min_value() >> (128 - bits)) {
+        None
+    } else {
+        Some(reference::divmod(a, b))
+    }
+}
+
+/// `(a / b, a % b)`, or `None` if `b` is zero
+fn checked_udivmod(a: u128, b: u128) -> Option<(u128, u128)> {
+    if b == 0 { None } else { Some(reference::udivmod(a, b)) }
+}
+
+/// Compares `intrinsic(a)` against `expected(a)`. Inputs for which `expected` returns `None`
+/// (undefined behavior) are skipped
+fn unary<T, R, F, G>(name: &str, gen: fn(&mut Rng) -> T, intrinsic: F, expected: G)
+    where T: Copy + Debug,
+          R: Debug + PartialEq,
+          F: Fn(T) -> R,
+          G: Fn(T) -> Option<R>
+{
+    let mut rng = Rng::new(SEED);
+    for _ in 0..ITERATIONS {
+        let a = gen(&mut rng);
+
+        if let Some(expected) = expected(a) {
+            let result = intrinsic(a);
+            assert!(result == expected,
+                    "{}({:?}) = {:?}, expected {:?} (seed: {})",
+                    name,
+                    a,
+                    result,
+                    expected,
+                    rng.seed);
+        }
+    }
+}
+
+/// Compares `intrinsic(a, b)` against `expected(a, b)`. Inputs for which `expected` returns `None`
+/// are skipped
+fn binary<T, U, R, F, G>(name: &str,
+                         gen_a: fn(&mut Rng) -> T,
+                         gen_b: fn(&mut Rng) -> U,
+                         intrinsic: F,
+                         expected: G)
+    where T: Copy + Debug,
+          U: Copy + Debug,
+          R: Debug + PartialEq,
+          F: Fn(T, U) -> R,
+          G: Fn(T, U) -> Option<R>
+{
+    let mut rng = Rng::new(SEED);
+    for _ in 0..ITERATIONS {
+        let a = gen_a(&mut rng);
+        let b = gen_b(&mut rng);
+
+        if let Some(expected) = expected(a, b) {
+            let result = intrinsic(a, b);
+            assert!(result == expected,
+                    "{}({:?}, {:?}) = {:?}, expected {:?} (seed: {})",
+                    name,
+                    a,
+                    b,
+                    result,
+                    expected,
+                    rng.seed);
+        }
+    }
+}
+
+fn shift64(rng: &mut Rng) -> i32 {
+    rng.shift(64) as i32
+}
+
+fn shift128(rng: &mut Rng) -> i32 {
+    rng.shift(128) as i32
+}
+
+/// Whether calling `symbol` with `args` aborts, which is what the `*v*` intrinsics do on overflow
+///
+/// The call is made by the `abort_child` test, in a child process.
+fn aborts(symbol: &str, args: &[i128]) -> bool {
+    let call = args.iter().fold(String::from(symbol), |call, arg| format!("{} {}", call, arg));
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args(&["abort_child", "--exact", "--test-threads=1"])
+        .env(ABORT_CALL, &call)
+        .output()
+        .unwrap();
+
+    !output.status.success()
+}
+
+/// Checks that the `*v*` intrinsic `symbol` aborts on the (overflowing) `inputs`
+fn check_aborts(symbol: &str, inputs: &[&[i128]]) {
+    for args in inputs {
+        assert!(aborts(symbol, args), "{}{:?} didn't abort", symbol, args);
+    }
+}
+
+/// Makes the call requested by the parent process, if any. See `aborts`
+#[test]
+fn abort_child() {
+    let call = match env::var(ABORT_CALL) {
+        Ok(call) => call,
+        Err(_) => return,
+    };
+    let mut words = call.split(' ');
+    let symbol = words.next().unwrap();
+    // NOTE unused if none of the `*v*` intrinsics was built
+    #[allow(unused_variables)]
+    let args = words.map(|word| word.parse().unwrap()).collect::<Vec<i128>>();
+
+    #[allow(unused_unsafe)]
+    unsafe {
+        match symbol {
+            #[cfg(intrinsic = "__absvsi2")]
+            "__absvsi2" => {
+                integer::__absvsi2(args[0] as i32);
+            }
+            #[cfg(intrinsic = "__absvdi2")]
+            "__absvdi2" => {
+                integer::__absvdi2(args[0] as i64);
+            }
+            #[cfg(intrinsic = "__absvti2")]
+            "__absvti2" => {
+                integer::__absvti2(args[0]);
+            }
+            #[cfg(intrinsic = "__addvsi3")]
+            "__addvsi3" => {
+                integer::__addvsi3(args[0] as i32, args[1] as i32);
+            }
+            #[cfg(intrinsic = "__addvdi3")]
+            "__addvdi3" => {
+                integer::__addvdi3(args[0] as i64, args[1] as i64);
+            }
+            #[cfg(intrinsic = "__addvti3")]
+            "__addvti3" => {
+                integer::__addvti3(args[0], args[1]);
+            }
+            #[cfg(intrinsic = "__mulvsi3")]
+            "__mulvsi3" => {
+                integer::__mulvsi3(args[0] as i32, args[1] as i32);
+            }
+            #[cfg(intrinsic = "__mulvdi3")]
+            "__mulvdi3" => {
+                integer::__mulvdi3(args[0] as i64, args[1] as i64);
+            }
+            #[cfg(intrinsic = "__mulvti3")]
+            "__mulvti3" => {
+                integer::__mulvti3(args[0], args[1]);
+            }
+            #[cfg(intrinsic = "__negvsi2")]
+            "__negvsi2" => {
+                integer::__negvsi2(args[0] as i32);
+            }
+            #[cfg(intrinsic = "__negvdi2")]
+            "__negvdi2" => {
+                integer::__negvdi2(args[0] as i64);
+            }
+            #[cfg(intrinsic = "__negvti2")]
+            "__negvti2" => {
+                integer::__negvti2(args[0]);
+            }
+            #[cfg(intrinsic = "__subvsi3")]
+            "__subvsi3" => {
+                integer::__subvsi3(args[0] as i32, args[1] as i32);
+            }
+            #[cfg(intrinsic = "__subvdi3")]
+            "__subvdi3" => {
+                integer::__subvdi3(args[0] as i64, args[1] as i64);
+            }
+            #[cfg(intrinsic = "__subvti3")]
+            "__subvti3" => {
+                integer::__subvti3(args[0], args[1]);
+            }
+            _ => panic!("unknown intrinsic: {}", symbol),
+        }
+    }
+}
+
+// Division
+
+#[cfg(intrinsic = "__divsi3")]
+#[test]
+fn divsi3() {
+    binary("__divsi3",
+           Rng::i32,
+           Rng::i32,
+           |a, b| unsafe { integer::__divsi3(a, b) },
+           |a, b| checked_divmod(a as i128, b as i128, 32).map(|qr| qr.0 as i32));
+}
+
+#[cfg(intrinsic = "__divdi3")]
+#[test]
+fn divdi3() {
+    binary("__divdi3",
+           Rng::i64,
+           Rng::i64,
+           |a, b| unsafe { integer::__divdi3(a, b) },
+           |a, b| checked_divmod(a as i128, b as i128, 64).map(|qr| qr.0 as i64));
+}
+
+#[cfg(intrinsic = "__divti3")]
+#[test]
+fn divti3() {
+    binary("__divti3",
+           Rng::i128,
+           Rng::i128,
+           |a, b| unsafe { integer::__divti3(a, b) },
+           |a, b| checked_divmod(a, b, 128).map(|qr| qr.0));
+}
+
+#[cfg(intrinsic = "__modsi3")]
+#[test]
+fn modsi3() {
+    binary("__modsi3",
+           Rng::i32,
+           Rng::i32,
+           |a, b| unsafe { integer::__modsi3(a, b) },
+           |a, b| checked_divmod(a as i128, b as i128, 32).map(|qr| qr.1 as i32));
+}
+
+#[cfg(intrinsic = "__moddi3")]
+#[test]
+fn moddi3() {
+    binary("__moddi3",
+           Rng::i64,
+           Rng::i64,
+           |a, b| unsafe { integer::__moddi3(a, b) },
+           |a, b| checked_divmod(a as i128, b as i128, 64).map(|qr| qr.1 as i64));
+}
+
+#[cfg(intrinsic = "__modti3")]
+#[test]
+fn modti3() {
+    binary("__modti3",
+           Rng::i128,
+           Rng::i128,
+           |a, b| unsafe { integer::__modti3(a, b) },
+           |a, b| checked_divmod(a, b, 128).map(|qr| qr.1));
+}
+
+#[cfg(intrinsic = "__divmodsi4")]
+#[test]
+fn divmodsi4() {
+    binary("__divmodsi4",
+           Rng::i32,
+           Rng::i32,
+           |a, b| {
+               let mut rem = 0;
+               let quot = unsafe { integer::__divmodsi4(a, b, &mut rem) };
+               (quot, rem)
+           },
+           |a, b| {
+               checked_divmod(a as i128, b as i128, 32).map(|(q, r)| (q as i32, r as i32))
+           });
+}
+
+#[cfg(intrinsic = "__divmoddi4")]
+#[test]
+fn divmoddi4() {
+    binary("__divmoddi4",
+           Rng::i64,
+           Rng::i64,
+           |a, b| {
+               let mut rem = 0;
+               let quot = unsafe { integer::__divmoddi4(a, b, &mut rem) };
+               (quot, rem)
+           },
+           |a, b| {
+               checked_divmod(a as i128, b as i128, 64).map(|(q, r)| (q as i64, r as i64))
+           });
+}
+
+#[cfg(intrinsic = "__udivsi3")]
+#[test]
+fn udivsi3() {
+    binary("__udivsi3",
+           Rng::u32,
+           Rng::u32,
+           |a, b| unsafe { integer::__udivsi3(a, b) },
+           |a, b| checked_udivmod(a as u128, b as u128).map(|qr| qr.0 as u32));
+}
+
+#[cfg(intrinsic = "__udivdi3")]
+#[test]
+fn udivdi3() {
+    binary("__udivdi3",
+           Rng::u64,
+           Rng::u64,
+           |a, b| unsafe { integer::__udivdi3(a, b) },
+           |a, b| checked_udivmod(a as u128, b as u128).map(|qr| qr.0 as u64));
+}
+
+#[cfg(intrinsic = "__udivti3")]
+#[test]
+fn udivti3() {
+    binary("__udivti3",
+           Rng::u128,
+           Rng::u128,
+           |a, b| unsafe { integer::__udivti3(a, b) },
+           |a, b| checked_udivmod(a, b).map(|qr| qr.0));
+}
+
+#[cfg(intrinsic = "__umodsi3")]
+#[test]
+fn umodsi3() {
+    binary("__umodsi3",
+           Rng::u32,
+           Rng::u32,
+           |a, b| unsafe { integer::__umodsi3(a, b) },
+           |a, b| checked_udivmod(a as u128, b as u128).map(|qr| qr.1 as u32));
+}
+
+#[cfg(intrinsic = "__umoddi3")]
+#[test]
+fn umoddi3() {
+    binary("__umoddi3",
+           Rng::u64,
+           Rng::u64,
+           |a, b| unsafe { integer::__umoddi3(a, b) },
+           |a, b| checked_udivmod(a as u128, b as u128).map(|qr| qr.1 as u64));
+}
+
+#[cfg(intrinsic = "__umodti3")]
+#[test]
+fn umodti3() {
+    binary("__umodti3",
+           Rng::u128,
+           Rng::u128,
+           |a, b| unsafe { integer::__umodti3(a, b) },
+           |a, b| checked_udivmod(a, b).map(|qr| qr.1));
+}
+
+#[cfg(intrinsic = "__udivmodsi4")]
+#[test]
+fn udivmodsi4() {
+    binary("__udivmodsi4",
+           Rng::u32,
+           Rng::u32,
+           |a, b| {
+               let mut rem = 0;
+               let quot = unsafe { integer::__udivmodsi4(a, b, &mut rem) };
+               (quot, rem)
+           },
+           |a, b| checked_udivmod(a as u128, b as u128).map(|(q, r)| (q as u32, r as u32)));
+}
+
+#[cfg(intrinsic = "__udivmoddi4")]
+#[test]
+fn udivmoddi4() {
+    binary("__udivmoddi4",
+           Rng::u64,
+           Rng::u64,
+           |a, b| {
+               let mut rem = 0;
+               let quot = unsafe { integer::__udivmoddi4(a, b, &mut rem) };
+               (quot, rem)
+           },
+           |a, b| checked_udivmod(a as u128, b as u128).map(|(q, r)| (q as u64, r as u64)));
+}
+
+#[cfg(intrinsic = "__udivmodti4")]
+#[test]
+fn udivmodti4() {
+    binary("__udivmodti4",
+           Rng::u128,
+           Rng::u128,
+           |a, b| {
+               let mut rem = 0;
+               let quot = unsafe { integer::__udivmodti4(a, b, &mut rem) };
+               (quot, rem)
+           },
+           |a, b| checked_udivmod(a, b));
+}
+
+// Multiplication
+
+#[cfg(intrinsic = "__muldi3")]
+#[test]
+fn muldi3() {
+    binary("__muldi3",
+           Rng::i64,
+           Rng::i64,
+           |a, b| unsafe { integer::__muldi3(a, b) },
+           |a, b| Some(reference::mul(a as i128, b as i128, 64).0 as i64));
+}
+
+#[cfg(intrinsic = "__multi3")]
+#[test]
+fn multi3() {
+    binary("__multi3",
+           Rng::i128,
+           Rng::i128,
+           |a, b| unsafe { integer::__multi3(a, b) },
+           |a, b| Some(reference::mul(a, b, 128).0));
+}
+
+#[cfg(intrinsic = "__mulosi4")]
+#[test]
+fn mulosi4() {
+    binary("__mulosi4",
+           Rng::i32,
+           Rng::i32,
+           |a, b| {
+               let mut overflow = 0;
+               let product = unsafe { integer::__mulosi4(a, b, &mut overflow) };
+               (product, overflow != 0)
+           },
+           |a, b| {
+               let (product, overflow) = reference::mul(a as i128, b as i128, 32);
+               Some((product as i32, overflow))
+           });
+}
+
+#[cfg(intrinsic = "__mulodi4")]
+#[test]
+fn mulodi4() {
+    binary("__mulodi4",
+           Rng::i64,
+           Rng::i64,
+           |a, b| {
+               let mut overflow = 0;
+               let product = unsafe { integer::__mulodi4(a, b, &mut overflow) };
+               (product, overflow != 0)
+           },
+           |a, b| {
+               let (product, overflow) = reference::mul(a as i128, b as i128, 64);
+               Some((product as i64, overflow))
+           });
+}
+
+#[cfg(intrinsic = "__muloti4")]
+#[test]
+fn muloti4() {
+    binary("__muloti4",
+           Rng::i128,
+           Rng::i128,
+           |a, b| {
+               let mut overflow = 0;
+               let product = unsafe { integer::__muloti4(a, b, &mut overflow) };
+               (product, overflow != 0)
+           },
+           |a, b| {
+               let (product, overflow) = reference::mul(a, b, 128);
+               Some((product, overflow))
+           });
+}
+
+// Arithmetic that aborts on overflow
+
+#[cfg(intrinsic = "__mulvsi3")]
+#[test]
+fn mulvsi3() {
+    binary("__mulvsi3",
+           Rng::i32,
+           Rng::i32,
+           |a, b| unsafe { integer::__mulvsi3(a, b) },
+           |a, b| match reference::mul(a as i128, b as i128, 32) {
+               (product, false) => Some(product as i32),
+               (_, true) => None,
+           });
+
+    let (min, max) = (i32::min_value() as i128, i32::max_value() as i128);
+    check_aborts("__mulvsi3", &[&[min, -1], &[max, 2], &[min, min], &[1 << 16, 1 << 15]]);
+}
+
+#[cfg(intrinsic = "__mulvdi3")]
+#[test]
+fn mulvdi3() {
+    binary("__mulvdi3",
+           Rng::i64,
+           Rng::i64,
+           |a, b| unsafe { integer::__mulvdi3(a, b) },
+           |a, b| match reference::mul(a as i128, b as i128, 64) {
+               (product, false) => Some(product as i64),
+               (_, true) => None,
+           });
+
+    let (min, max) = (i64::min_value() as i128, i64::max_value() as i128);
+    check_aborts("__mulvdi3", &[&[min, -1], &[max, 2], &[min, min], &[1 << 32, 1 << 31]]);
+}
+
+#[cfg(intrinsic = "__mulvti3")]
+#[test]
+fn mulvti3() {
+    binary("__mulvti3",
+           Rng::i128,
+           Rng::i128,
+           |a, b| unsafe { integer::__mulvti3(a, b) },
+           |a, b| match reference::mul(a, b, 128) {
+               (product, false) => Some(product),
+               (_, true) => None,
+           });
+
+    let (min, max) = (i128::min_value(), i128::max_value());
+    check_aborts("__mulvti3", &[&[min, -1], &[max, 2], &[min, min], &[1 << 64, 1 << 63]]);
+}
+
+#[cfg(intrinsic = "__addvsi3")]
+#[test]
+fn addvsi3() {
+    binary("__addvsi3",
+           Rng::i32,
+           Rng::i32,
+           |a, b| unsafe { integer::__addvsi3(a, b) },
+           |a, b| a.checked_add(b));
+
+    let (min, max) = (i32::min_value() as i128, i32::max_value() as i128);
+    check_aborts("__addvsi3", &[&[max, 1], &[min, -1]]);
+}
+
+#[cfg(intrinsic = "__addvdi3")]
+#[test]
+fn addvdi3() {
+    binary("__addvdi3",
+           Rng::i64,
+           Rng::i64,
+           |a, b| unsafe { integer::__addvdi3(a, b) },
+           |a, b| a.checked_add(b));
+
+    let (min, max) = (i64::min_value() as i128, i64::max_value() as i128);
+    check_aborts("__addvdi3", &[&[max, 1], &[min, -1]]);
+}
+
+#[cfg(intrinsic = "__addvti3")]
+#[test]
+fn addvti3() {
+    binary("__addvti3",
+           Rng::i128,
+           Rng::i128,
+           |a, b| unsafe { integer::__addvti3(a, b) },
+           |a, b| a.checked_add(b));
+
+    check_aborts("__addvti3", &[&[i128::max_value(), 1], &[i128::min_value(), -1]]);
+}
+
+#[cfg(intrinsic = "__subvsi3")]
+#[test]
+fn subvsi3() {
+    binary("__subvsi3",
+           Rng::i32,
+           Rng::i32,
+           |a, b| unsafe { integer::__subvsi3(a, b) },
+           |a, b| a.checked_sub(b));
+
+    let (min, max) = (i32::min_value() as i128, i32::max_value() as i128);
+    check_aborts("__subvsi3", &[&[min, 1], &[max, -1]]);
+}
+
+#[cfg(intrinsic = "__subvdi3")]
+#[test]
+fn subvdi3() {
+    binary("__subvdi3",
+           Rng::i64,
+           Rng::i64,
+           |a, b| unsafe { integer::__subvdi3(a, b) },
+           |a, b| a.checked_sub(b));
+
+    let (min, max) = (i64::min_value() as i128, i64::max_value() as i128);
+    check_aborts("__subvdi3", &[&[min, 1], &[max, -1]]);
+}
+
+#[cfg(intrinsic = "__subvti3")]
+#[test]
+fn subvti3() {
+    binary("__subvti3",
+           Rng::i128,
+           Rng::i128,
+           |a, b| unsafe { integer::__subvti3(a, b) },
+           |a, b| a.checked_sub(b));
+
+    check_aborts("__subvti3", &[&[i128::min_value(), 1], &[i128::max_value(), -1]]);
+}
+
+#[cfg(intrinsic = "__negvsi2")]
+#[test]
+fn negvsi2() {
+    unary("__negvsi2",
+          Rng::i32,
+          |a| unsafe { integer::__negvsi2(a) },
+          |a| a.checked_neg());
+
+    check_aborts("__negvsi2", &[&[i32::min_value() as i128]]);
+}
+
+#[cfg(intrinsic = "__negvdi2")]
+#[test]
+fn negvdi2() {
+    unary("__negvdi2",
+          Rng::i64,
+          |a| unsafe { integer::__negvdi2(a) },
+          |a| a.checked_neg());
+
+    check_aborts("__negvdi2", &[&[i64::min_value() as i128]]);
+}
+
+#[cfg(intrinsic = "__negvti2")]
+#[test]
+fn negvti2() {
+    unary("__negvti2",
+          Rng::i128,
+          |a| unsafe { integer::__negvti2(a) },
+          |a| a.checked_neg());
+
+    check_aborts("__negvti2", &[&[i128::min_value()]]);
+}
+
+#[cfg(intrinsic = "__absvsi2")]
+#[test]
+fn absvsi2() {
+    unary("__absvsi2",
+          Rng::i32,
+          |a| unsafe { integer::__absvsi2(a) },
+          |a| a.checked_abs());
+
+    check_aborts("__absvsi2", &[&[i32::min_value() as i128]]);
+}
+
+#[cfg(intrinsic = "__absvdi2")]
+#[test]
+fn absvdi2() {
+    unary("__absvdi2",
+          Rng::i64,
+          |a| unsafe { integer::__absvdi2(a) },
+          |a| a.checked_abs());
+
+    check_aborts("__absvdi2", &[&[i64::min_value() as i128]]);
+}
+
+#[cfg(intrinsic = "__absvti2")]
+#[test]
+fn absvti2() {
+    unary("__absvti2",
+          Rng::i128,
+          |a| unsafe { integer::__absvti2(a) },
+          |a| a.checked_abs());
+
+    check_aborts("__absvti2", &[&[i128::min_value()]]);
+}
+
+#[cfg(intrinsic = "__negdi2")]
+#[test]
+fn negdi2() {
+    unary("__negdi2",
+          Rng::i64,
+          |a| unsafe { integer::__negdi2(a) },
+          |a| Some(a.wrapping_neg()));
+}
+
+#[cfg(intrinsic = "__negti2")]
+#[test]
+fn negti2() {
+    unary("__negti2",
+          Rng::i128,
+          |a| unsafe { integer::__negti2(a) },
+          |a| Some(a.wrapping_neg()));
+}
+
+// Shifts
+
+#[cfg(intrinsic = "__ashldi3")]
+#[test]
+fn ashldi3() {
+    binary("__ashldi3",
+           Rng::i64,
+           shift64,
+           |a, b| unsafe { integer::__ashldi3(a, b) },
+           |a, b| Some(a << b));
+}
+
+#[cfg(intrinsic = "__ashlti3")]
+#[test]
+fn ashlti3() {
+    binary("__ashlti3",
+           Rng::i128,
+           shift128,
+           |a, b| unsafe { integer::__ashlti3(a, b) },
+           |a, b| Some(a << b));
+}
+
+#[cfg(intrinsic = "__ashrdi3")]
+#[test]
+fn ashrdi3() {
+    binary("__ashrdi3",
+           Rng::i64,
+           shift64,
+           |a, b| unsafe { integer::__ashrdi3(a, b) },
+           |a, b| Some(a >> b));
+}
+
+#[cfg(intrinsic = "__ashrti3")]
+#[test]
+fn ashrti3() {
+    binary("__ashrti3",
+           Rng::i128,
+           shift128,
+           |a, b| unsafe { integer::__ashrti3(a, b) },
+           |a, b| Some(a >> b));
+}
+
+#[cfg(intrinsic = "__lshrdi3")]
+#[test]
+fn lshrdi3() {
+    binary("__lshrdi3",
+           Rng::i64,
+           shift64,
+           |a, b| unsafe { integer::__lshrdi3(a, b) },
+           |a, b| Some((a as u64 >> b) as i64));
+}
+
+#[cfg(intrinsic = "__lshrti3")]
+#[test]
+fn lshrti3() {
+    binary("__lshrti3",
+           Rng::i128,
+           shift128,
+           |a, b| unsafe { integer::__lshrti3(a, b) },
+           |a, b| Some((a as u128 >> b) as i128));
+}
+
+// Comparisons: 0 if `a < b`, 1 if `a == b`, 2 if `a > b`
+
+#[cfg(intrinsic = "__cmpdi2")]
+#[test]
+fn cmpdi2() {
+    binary("__cmpdi2",
+           Rng::i64,
+           Rng::i64,
+           |a, b| unsafe { integer::__cmpdi2(a, b) },
+           |a, b| Some(a.cmp(&b) as i32 + 1));
+}
+
+#[cfg(intrinsic = "__cmpti2")]
+#[test]
+fn cmpti2() {
+    binary("__cmpti2",
+           Rng::i128,
+           Rng::i128,
+           |a, b| unsafe { integer::__cmpti2(a, b) },
+           |a, b| Some(a.cmp(&b) as i32 + 1));
+}
+
+#[cfg(intrinsic = "__ucmpdi2")]
+#[test]
+fn ucmpdi2() {
+    binary("__ucmpdi2",
+           Rng::u64,
+           Rng::u64,
+           |a, b| unsafe { integer::__ucmpdi2(a, b) },
+           |a, b| Some(a.cmp(&b) as i32 + 1));
+}
+
+#[cfg(intrinsic = "__ucmpti2")]
+#[test]
+fn ucmpti2() {
+    binary("__ucmpti2",
+           Rng::u128,
+           Rng::u128,
+           |a, b| unsafe { integer::__ucmpti2(a, b) },
+           |a, b| Some(a.cmp(&b) as i32 + 1));
+}
+
+// Bit manipulation
+
+// NOTE the number of leading or trailing zeros of 0 is undefined
+#[cfg(intrinsic = "__clzsi2")]
+#[test]
+fn clzsi2() {
+    unary("__clzsi2",
+          Rng::i32,
+          |a| unsafe { integer::__clzsi2(a) },
+          |a| if a == 0 { None } else { Some(a.leading_zeros() as i32) });
+}
+
+#[cfg(intrinsic = "__clzdi2")]
+#[test]
+fn clzdi2() {
+    unary("__clzdi2",
+          Rng::i64,
+          |a| unsafe { integer::__clzdi2(a) },
+          |a| if a == 0 { None } else { Some(a.leading_zeros() as i32) });
+}
+
+#[cfg(intrinsic = "__clzti2")]
+#[test]
+fn clzti2() {
+    unary("__clzti2",
+          Rng::i128,
+          |a| unsafe { integer::__clzti2(a) },
+          |a| if a == 0 { None } else { Some(a.leading_zeros() as i32) });
+}
+
+#[cfg(intrinsic = "__ctzsi2")]
+#[test]
+fn ctzsi2() {
+    unary("__ctzsi2",
+          Rng::i32,
+          |a| unsafe { integer::__ctzsi2(a) },
+          |a| if a == 0 { None } else { Some(a.trailing_zeros() as i32) });
+}
+
+#[cfg(intrinsic = "__ctzdi2")]
+#[test]
+fn ctzdi2() {
+    unary("__ctzdi2",
+          Rng::i64,
+          |a| unsafe { integer::__ctzdi2(a) },
+          |a| if a == 0 { None } else { Some(a.trailing_zeros() as i32) });
+}
+
+#[cfg(intrinsic = "__ctzti2")]
+#[test]
+fn ctzti2() {
+    unary("__ctzti2",
+          Rng::i128,
+          |a| unsafe { integer::__ctzti2(a) },
+          |a| if a == 0 { None } else { Some(a.trailing_zeros() as i32) });
+}
+
+// 1 + the index of the least significant set bit; 0 if no bit is set
+#[cfg(intrinsic = "__ffsdi2")]
+#[test]
+fn ffsdi2() {
+    unary("__ffsdi2",
+          Rng::i64,
+          |a| unsafe { integer::__ffsdi2(a) },
+          |a| Some(if a == 0 { 0 } else { a.trailing_zeros() as i32 + 1 }));
+}
+
+#[cfg(intrinsic = "__ffsti2")]
+#[test]
+fn ffsti2() {
+    unary("__ffsti2",
+          Rng::i128,
+          |a| unsafe { integer::__ffsti2(a) },
+          |a| Some(if a == 0 { 0 } else { a.trailing_zeros() as i32 + 1 }));
+}
+
+#[cfg(intrinsic = "__popcountsi2")]
+#[test]
+fn popcountsi2() {
+    unary("__popcountsi2",
+          Rng::i32,
+          |a| unsafe { integer::__popcountsi2(a) },
+          |a| Some(a.count_ones() as i32));
+}
+
+#[cfg(intrinsic = "__popcountdi2")]
+#[test]
+fn popcountdi2() {
+    unary("__popcountdi2",
+          Rng::i64,
+          |a| unsafe { integer::__popcountdi2(a) },
+          |a| Some(a.count_ones() as i32));
+}
+
+#[cfg(intrinsic = "__popcountti2")]
+#[test]
+fn popcountti2() {
+    unary("__popcountti2",
+          Rng::i128,
+          |a| unsafe { integer::__popcountti2(a) },
+          |a| Some(a.count_ones() as i32));
+}
+
+#[cfg(intrinsic = "__paritysi2")]
+#[test]
+fn paritysi2() {
+    unary("__paritysi2",
+          Rng::i32,
+          |a| unsafe { integer::__paritysi2(a) },
+          |a| Some(a.count_ones() as i32 & 1));
+}
+
+#[cfg(intrinsic = "__paritydi2")]
+#[test]
+fn paritydi2() {
+    unary("__paritydi2",
+          Rng::i64,
+          |a| unsafe { integer::__paritydi2(a) },
+          |a| Some(a.count_ones() as i32 & 1));
+}
+
+#[cfg(intrinsic = "__parityti2")]
+#[test]
+fn parityti2() {
+    unary("__parityti2",
+          Rng::i128,
+          |a| unsafe { integer::__parityti2(a) },
+          |a| Some(a.count_ones() as i32 & 1));
+}
+
+#[cfg(intrinsic = "__bswapsi2")]
+#[test]
+fn bswapsi2() {
+    unary("__bswapsi2",
+          Rng::u32,
+          |a| unsafe { integer::__bswapsi2(a) },
+          |a| Some(a.swap_bytes()));
+}
+
+#[cfg(intrinsic = "__bswapdi2")]
+#[test]
+fn bswapdi2() {
+    unary("__bswapdi2",
+          Rng::u64,
+          |a| unsafe { integer::__bswapdi2(a) },
+          |a| Some(a.swap_bytes()));
+}
@@ -0,0 +1,245 @@
+//! Tests of the safe wrappers
+//!
+//! Unlike the conformance tests of the intrinsics, these cover the inputs the intrinsics don't
+//! define a result for (NaNs, infinities and out of range values), which the wrappers handle
+//! themselves, and the edge cases of the documented behavior of each wrapper.
+
+#![allow(dead_code)]
+
+extern crate compiler_rt;
+
+#[allow(unused_imports)]
+use compiler_rt::*;
+
+#[cfg(intrinsic = "__mulosi4")]
+#[test]
+fn checked_mul_i32() {
+    const MAX: i32 = i32::MAX;
+    const MIN: i32 = i32::MIN;
+
+    assert_eq!(compiler_rt::checked_mul_i32(6, -7), Some(-42));
+    assert_eq!(compiler_rt::checked_mul_i32(MAX, 1), Some(MAX));
+    assert_eq!(compiler_rt::checked_mul_i32(MIN, 1), Some(MIN));
+    assert_eq!(compiler_rt::checked_mul_i32(MIN, 0), Some(0));
+    assert_eq!(compiler_rt::checked_mul_i32(-(1 << 15), 1 << 16), Some(MIN));
+    assert_eq!(compiler_rt::checked_mul_i32(1 << 15, 1 << 16), None);
+    assert_eq!(compiler_rt::checked_mul_i32(MAX, 2), None);
+    assert_eq!(compiler_rt::checked_mul_i32(MAX, -2), None);
+    assert_eq!(compiler_rt::checked_mul_i32(MIN, -1), None);
+    assert_eq!(compiler_rt::checked_mul_i32(-1, MIN), None);
+    assert_eq!(compiler_rt::checked_mul_i32(MIN, MIN), None);
+}
+
+#[cfg(intrinsic = "__mulodi4")]
+#[test]
+fn checked_mul_i64() {
+    const MAX: i64 = i64::MAX;
+    const MIN: i64 = i64::MIN;
+
+    assert_eq!(compiler_rt::checked_mul_i64(6, -7), Some(-42));
+    assert_eq!(compiler_rt::checked_mul_i64(MAX, 1), Some(MAX));
+    assert_eq!(compiler_rt::checked_mul_i64(MIN, 1), Some(MIN));
+    assert_eq!(compiler_rt::checked_mul_i64(MIN, 0), Some(0));
+    assert_eq!(compiler_rt::checked_mul_i64(-(1 << 31), 1 << 32), Some(MIN));
+    assert_eq!(compiler_rt::checked_mul_i64(1 << 31, 1 << 32), None);
+    assert_eq!(compiler_rt::checked_mul_i64(MAX, 2), None);
+    assert_eq!(compiler_rt::checked_mul_i64(MAX, -2), None);
+    assert_eq!(compiler_rt::checked_mul_i64(MIN, -1), None);
+    assert_eq!(compiler_rt::checked_mul_i64(-1, MIN), None);
+    assert_eq!(compiler_rt::checked_mul_i64(MIN, MIN), None);
+}
+
+#[cfg(intrinsic = "__muloti4")]
+#[test]
+fn checked_mul_i128() {
+    const MAX: i128 = i128::MAX;
+    const MIN: i128 = i128::MIN;
+
+    assert_eq!(compiler_rt::checked_mul_i128(6, -7), Some(-42));
+    assert_eq!(compiler_rt::checked_mul_i128(MAX, 1), Some(MAX));
+    assert_eq!(compiler_rt::checked_mul_i128(MIN, 1), Some(MIN));
+    assert_eq!(compiler_rt::checked_mul_i128(MIN, 0), Some(0));
+    assert_eq!(compiler_rt::checked_mul_i128(-(1 << 63), 1 << 64), Some(MIN));
+    assert_eq!(compiler_rt::checked_mul_i128(1 << 63, 1 << 64), None);
+    assert_eq!(compiler_rt::checked_mul_i128(MAX, 2), None);
+    assert_eq!(compiler_rt::checked_mul_i128(MAX, -2), None);
+    assert_eq!(compiler_rt::checked_mul_i128(MIN, -1), None);
+    assert_eq!(compiler_rt::checked_mul_i128(-1, MIN), None);
+    assert_eq!(compiler_rt::checked_mul_i128(MIN, MIN), None);
+}
+
+/// Checks a saturating conversion against the bounds of its integer type; `$max` is the smallest
+/// value of the float type that's out of range
+macro_rules! saturating {
+    ($($name:ident: $float:ident -> $int:ident = $intrinsic:tt, $max:expr;)+) => {
+        $(
+            #[cfg(intrinsic = $intrinsic)]
+            #[test]
+            fn $name() {
+                let max: $float = $max;
+                let min = $int::MIN as $float;
+
+                assert_eq!(compiler_rt::$name($float::NAN), 0);
+                assert_eq!(compiler_rt::$name(-$float::NAN), 0);
+                assert_eq!(compiler_rt::$name($float::INFINITY), $int::MAX);
+                assert_eq!(compiler_rt::$name($float::NEG_INFINITY), $int::MIN);
+                assert_eq!(compiler_rt::$name(max), $int::MAX);
+                assert_eq!(compiler_rt::$name(max * 2.), $int::MAX);
+                assert_eq!(compiler_rt::$name(min - max), $int::MIN);
+                assert_eq!(compiler_rt::$name(-1.) as i128, if $int::MIN == 0 { 0 } else { -1 });
+                assert_eq!(compiler_rt::$name(-0.), 0);
+                assert_eq!(compiler_rt::$name(0.99), 0);
+                assert_eq!(compiler_rt::$name(-0.99), 0);
+                assert_eq!(compiler_rt::$name(42.5), 42);
+
+                // The values right inside the range
+                let below_max = $float::from_bits(max.to_bits() - 1);
+                assert_eq!(compiler_rt::$name(below_max), below_max as $int);
+                assert_eq!(compiler_rt::$name(min), $int::MIN);
+            }
+        )+
+    }
+}
+
+saturating! {
+    f64_to_i32_sat: f64 -> i32 = "__fixdfsi", 2147483648.;
+    f64_to_i64_sat: f64 -> i64 = "__fixdfdi", 9223372036854775808.;
+    f64_to_u32_sat: f64 -> u32 = "__fixunsdfsi", 4294967296.;
+    f64_to_u64_sat: f64 -> u64 = "__fixunsdfdi", 18446744073709551616.;
+    f32_to_i32_sat: f32 -> i32 = "__fixsfsi", 2147483648.;
+    f32_to_i64_sat: f32 -> i64 = "__fixsfdi", 9223372036854775808.;
+    f32_to_u32_sat: f32 -> u32 = "__fixunssfsi", 4294967296.;
+    f32_to_u64_sat: f32 -> u64 = "__fixunssfdi", 18446744073709551616.;
+}
+
+/// Checks a `powi` wrapper; `$tiny` is the smallest positive subnormal of the float type
+macro_rules! powi {
+    ($($name:ident: $float:ident = $intrinsic:tt, $tiny:expr;)+) => {
+        $(
+            #[cfg(intrinsic = $intrinsic)]
+            #[test]
+            fn $name() {
+                let inf = $float::INFINITY;
+
+                assert_eq!(compiler_rt::$name(2., 10), 1024.);
+                assert_eq!(compiler_rt::$name(-2., 3), -8.);
+                assert_eq!(compiler_rt::$name(2., -2), 0.25);
+                assert_eq!(compiler_rt::$name(-3., 0), 1.);
+                assert_eq!(compiler_rt::$name(1., i32::MIN), 1.);
+                assert_eq!(compiler_rt::$name(-1., i32::MAX), -1.);
+
+                // Overflow and underflow
+                assert_eq!(compiler_rt::$name(2., i32::MAX), inf);
+                assert_eq!(compiler_rt::$name(-2., i32::MAX), -inf);
+                assert_eq!(compiler_rt::$name(2., i32::MIN), 0.);
+                assert_eq!(compiler_rt::$name($tiny, -1), inf);
+
+                // Zeros and infinities
+                assert_eq!(compiler_rt::$name(0., -1), inf);
+                assert_eq!(compiler_rt::$name(-0., -1), -inf);
+                assert_eq!(compiler_rt::$name(-0., 3).to_bits(), (-0. as $float).to_bits());
+                assert_eq!(compiler_rt::$name(inf, 2), inf);
+                assert_eq!(compiler_rt::$name(-inf, 3), -inf);
+                assert_eq!(compiler_rt::$name(inf, -1), 0.);
+
+                // NaN propagates, except to the zeroth power
+                assert!(compiler_rt::$name($float::NAN, 1).is_nan());
+                assert!(compiler_rt::$name($float::NAN, -2).is_nan());
+                assert_eq!(compiler_rt::$name($float::NAN, 0), 1.);
+            }
+        )+
+    }
+}
+
+powi! {
+    powi_f32: f32 = "__powisf2", f32::from_bits(1);
+    powi_f64: f64 = "__powidf2", f64::from_bits(1);
+}
+
+/// Checks the complex wrappers of a float type against the cases of C99 Annex G
+macro_rules! complex {
+    ($($name:ident: $float:ident, $mul:ident = $mul_intrinsic:tt, $div:ident = $div_intrinsic:tt;)+) => {
+        $(
+            mod $name {
+                use compiler_rt::Complex;
+
+                fn c(re: $float, im: $float) -> Complex<$float> {
+                    Complex { re: re, im: im }
+                }
+
+                fn is_inf(z: Complex<$float>) -> bool {
+                    z.re.is_infinite() || z.im.is_infinite()
+                }
+
+                fn is_nan(z: Complex<$float>) -> bool {
+                    z.re.is_nan() && z.im.is_nan()
+                }
+
+                #[cfg(intrinsic = $mul_intrinsic)]
+                #[test]
+                fn mul() {
+                    let inf = $float::INFINITY;
+                    let nan = $float::NAN;
+
+                    assert_eq!(::compiler_rt::$mul(c(1., 2.), c(3., 4.)), c(-5., 10.));
+                    assert_eq!(::compiler_rt::$mul(c(0., 1.), c(0., 1.)), c(-1., 0.));
+                    assert_eq!(::compiler_rt::$mul(c(2., 0.), c(0., 0.)), c(0., 0.));
+
+                    // An infinite operand gives an infinite result, even times a NaN, where
+                    // `(ac - bd) + (ad + bc)i` gives NaN + NaN i
+                    assert!(is_inf(::compiler_rt::$mul(c(inf, 0.), c(1., 1.))));
+                    assert!(is_inf(::compiler_rt::$mul(c(inf, inf), c(1., 0.))));
+                    assert!(is_inf(::compiler_rt::$mul(c(1., 1.), c(nan, inf))));
+
+                    // Overflow of the partial products
+                    let max = $float::MAX;
+                    assert!(is_inf(::compiler_rt::$mul(c(max, max), c(max, max))));
+
+                    // Otherwise NaN stays NaN
+                    assert!(is_nan(::compiler_rt::$mul(c(nan, 0.), c(1., 1.))));
+                    assert!(is_nan(::compiler_rt::$mul(c(inf, 0.), c(0., 0.))));
+                }
+
+                #[cfg(intrinsic = $div_intrinsic)]
+                #[test]
+                fn div() {
+                    let inf = $float::INFINITY;
+                    let nan = $float::NAN;
+
+                    assert_eq!(::compiler_rt::$div(c(-5., 10.), c(3., 4.)), c(1., 2.));
+                    assert_eq!(::compiler_rt::$div(c(1., 0.), c(0., 1.)), c(0., -1.));
+
+                    // The divisor is scaled, so its squared magnitude doesn't overflow or underflow
+                    let big = (2. as $float).powi($float::MAX_EXP / 2 + 8);
+                    let quotient = 1. / (2. * big);
+                    assert_eq!(::compiler_rt::$div(c(1., 0.), c(big, big)), c(quotient, -quotient));
+                    let min = $float::MIN_POSITIVE;
+                    assert_eq!(::compiler_rt::$div(c(min, min), c(min, min)), c(1., 0.));
+
+                    // A nonzero finite number divided by zero is infinite
+                    let z = ::compiler_rt::$div(c(1., 1.), c(0., 0.));
+                    assert_eq!((z.re, z.im), (inf, inf));
+                    assert!(is_inf(::compiler_rt::$div(c(-1., 0.), c(0., 0.))));
+
+                    // Infinite by finite is infinite and finite by infinite is zero
+                    assert!(is_inf(::compiler_rt::$div(c(inf, 0.), c(1., 1.))));
+                    assert!(is_inf(::compiler_rt::$div(c(inf, inf), c(2., 0.))));
+                    let z = ::compiler_rt::$div(c(1., 1.), c(inf, 0.));
+                    assert_eq!((z.re, z.im), (0., 0.));
+                    let z = ::compiler_rt::$div(c(1., 1.), c(nan, inf));
+                    assert_eq!((z.re, z.im), (0., 0.));
+
+                    // Indeterminate forms are NaN
+                    assert!(is_nan(::compiler_rt::$div(c(0., 0.), c(0., 0.))));
+                    assert!(is_nan(::compiler_rt::$div(c(inf, inf), c(inf, inf))));
+                    assert!(is_nan(::compiler_rt::$div(c(nan, nan), c(1., 1.))));
+                }
+            }
+        )+
+    }
+}
+
+complex! {
+    c32: f32, mul_c32 = "__mulsc3", div_c32 = "__divsc3";
+    c64: f64, mul_c64 = "__muldc3", div_c64 = "__divdc3";
+}
@@ -0,0 +1,186 @@
+//! Runs compiler-rt's own unit tests (`test/builtins/Unit`) against the archive built by the build
+//! script
+//!
+//! Each `foo_test.c` is compiled on the host, linked against `libcompiler-rt.a` and executed. The
+//! archive comes before the system libraries on the command line, so every intrinsic that the
+//! archive defines is taken from it rather than from libgcc.
+
+extern crate serde_json;
+extern crate tempdir;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+use tempdir::TempDir;
+
+macro_rules! try {
+    ($e:expr) => {
+        $e.unwrap_or_else(|e| panic!("{} with {}", stringify!($e), e))
+    }
+}
+
+/// Intrinsics that upstream has a unit test for and that every archive defines; the test fails if any
+/// of them wasn't tested
+const EXPECTED: &'static [&'static str] = &["absvsi2",
+                                            "ashldi3",
+                                            "divdi3",
+                                            "divsi3",
+                                            "moddi3",
+                                            "modsi3",
+                                            "muldi3",
+                                            "popcountsi2",
+                                            "udivdi3",
+                                            "udivmoddi4",
+                                            "udivsi3",
+                                            "umodsi3"];
+
+/// Outcome of the unit test of an intrinsic
+enum Outcome {
+    Passed,
+    /// The test didn't compile or link
+    Error(String),
+    /// The test ran but exited with a non zero status
+    Failed(String),
+}
+
+#[test]
+fn builtins() {
+    let out_dir = Path::new(env!("OUT_DIR"));
+    let unit = out_dir.join("unit");
+    let archive = out_dir.join("libcompiler-rt.a");
+
+    // NOTE there's no archive when the Rust fallback is used
+    if !archive.exists() {
+        println!("no archive in {}; skipping", out_dir.display());
+        return;
+    }
+
+    // NOTE the build copies the unit tests of the checkout, if it has any
+    let tests = fs::read_dir(&unit)
+        .map(|entries| {
+            entries.filter(|entry| {
+                    try!(entry.as_ref()).file_name().to_string_lossy().ends_with("_test.c")
+                })
+                .count()
+        })
+        .unwrap_or(0);
+    assert!(tests > 0,
+            "the archive was built but there are no unit tests in {}",
+            unit.display());
+
+    let td = try!(TempDir::new("unit"));
+    let cc = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+
+    let mut outcomes = BTreeMap::new();
+    let mut tested = vec![];
+    let mut untested = vec![];
+    for (stem, symbols) in intrinsics(&out_dir.join("symbols.json")) {
+        let test = unit.join(format!("{}_test.c", stem));
+        if !test.exists() {
+            untested.push(symbols);
+            continue;
+        }
+
+        tested.push(stem);
+        outcomes.insert(symbols, run(&cc, &test, &unit.join("include"), &archive, td.path()));
+    }
+
+    let mut failures = 0;
+    for (symbols, outcome) in &outcomes {
+        let symbols = symbols.join(", ");
+
+        match *outcome {
+            Outcome::Passed => println!("{} ... ok", symbols),
+            Outcome::Error(ref output) => {
+                failures += 1;
+                println!("{} ... ERROR\n{}", symbols, output);
+            }
+            Outcome::Failed(ref output) => {
+                failures += 1;
+                println!("{} ... FAILED\n{}", symbols, output);
+            }
+        }
+    }
+
+    for symbols in &untested {
+        println!("{} ... skipped (no unit test)", symbols.join(", "));
+    }
+
+    println!("\n{} passed; {} failed; {} skipped",
+             outcomes.len() - failures,
+             failures,
+             untested.len());
+
+    assert!(failures == 0, "{} unit tests failed", failures);
+
+    let missing = EXPECTED.iter()
+        .filter(|stem| !tested.iter().any(|tested| tested == *stem))
+        .collect::<Vec<_>>();
+    assert!(missing.is_empty(),
+            "the unit tests of {:?} didn't run; {} did",
+            missing,
+            tested.len());
+}
+
+/// Compiles, links and runs the unit `test`
+fn run(cc: &str, test: &Path, include: &Path, archive: &Path, td: &Path) -> Outcome {
+    let exe = td.join(test.file_stem().unwrap());
+
+    let output = try!(Command::new(cc)
+        .args(&["-fno-builtin", "-O0"])
+        .arg("-I")
+        .arg(include)
+        .arg(test)
+        .arg(archive)
+        .arg("-lm")
+        .arg("-o")
+        .arg(&exe)
+        .output());
+    if !output.status.success() {
+        return Outcome::Error(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+
+    let output = try!(Command::new(&exe).output());
+    if output.status.success() {
+        Outcome::Passed
+    } else {
+        let mut log = String::from_utf8_lossy(&output.stdout).into_owned();
+        log.push_str(&String::from_utf8_lossy(&output.stderr));
+        Outcome::Failed(log)
+    }
+}
+
+/// Symbols of the archive grouped by the stem of the source that defines them, according to the
+/// symbol manifest. The unit test of `foo.c` is `foo_test.c`
+fn intrinsics(manifest: &Path) -> BTreeMap<String, Vec<String>> {
+    let json = &mut String::new();
+    try!(try!(File::open(manifest)).read_to_string(json));
+    let entries: Vec<BTreeMap<String, Value>> = try!(serde_json::from_str(json));
+
+    let field = |entry: &BTreeMap<String, Value>, field: &str| {
+        match entry[field] {
+            Value::String(ref s) => s.clone(),
+            _ => unreachable!(),
+        }
+    };
+
+    let mut intrinsics = BTreeMap::new();
+    for entry in &entries {
+        let source = PathBuf::from(field(entry, "source"));
+        let stem = source.file_stem().unwrap().to_string_lossy().into_owned();
+
+        intrinsics.entry(stem).or_insert_with(Vec::new).push(field(entry, "name"));
+    }
+
+    for symbols in intrinsics.values_mut() {
+        symbols.sort();
+        symbols.dedup();
+    }
+
+    intrinsics
+}